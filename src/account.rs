@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::CommandError,
+    components::Config,
+    credentials,
+    network::{NetworkRequest, NetworkResponse, NetworkTask},
+    poll::Poll,
+    read_state::ReadState,
+    state::AppState,
+};
+
+type Callback = Box<dyn FnMut()>;
+
+pub struct Account {
+    pub name: String,
+    pub req_tx: mpsc::UnboundedSender<NetworkRequest>,
+    pub resp_rx: mpsc::UnboundedReceiver<NetworkResponse>,
+    // While an account is inactive its state and credentials are parked here. The active
+    // account's slot holds whatever was swapped out last and is never read.
+    state: AppState,
+    username: String,
+    password: String,
+    server_url: String,
+    messages_poll: Poll<Callback>,
+    users_poll: Poll<Callback>,
+}
+
+impl Account {
+    fn spawn(
+        name: String,
+        server_url: String,
+        client: reqwest::Client,
+        allow_plaintext: bool,
+    ) -> Self {
+        let (req_tx, req_rx) = mpsc::unbounded_channel::<NetworkRequest>();
+        let (resp_tx, resp_rx) = mpsc::unbounded_channel::<NetworkResponse>();
+        req_tx
+            .send(NetworkRequest::SetServer(server_url.clone()))
+            .ok();
+        tokio::spawn(async move {
+            NetworkTask::new(client, allow_plaintext)
+                .run(req_rx, resp_tx)
+                .await;
+        });
+
+        let saved = credentials::load(&server_url);
+        let mut state = AppState::new();
+        state.read_state = ReadState::load(&server_url);
+        state.server_url = server_url.clone();
+
+        let req_tx_messages = req_tx.clone();
+        let messages_poll = Poll::new(
+            Duration::from_secs(10),
+            Box::new(move || {
+                req_tx_messages.send(NetworkRequest::FetchMessages).ok();
+            }) as Callback,
+        );
+        let req_tx_users = req_tx.clone();
+        let users_poll = Poll::new(
+            Duration::from_secs(60),
+            Box::new(move || {
+                req_tx_users.send(NetworkRequest::FetchUsers).ok();
+            }) as Callback,
+        );
+
+        Self {
+            name,
+            req_tx,
+            resp_rx,
+            state,
+            username: saved
+                .as_ref()
+                .map(|login| login.username.clone())
+                .unwrap_or_default(),
+            password: saved.map(|login| login.password).unwrap_or_default(),
+            server_url,
+            messages_poll,
+            users_poll,
+        }
+    }
+
+    fn poll(&mut self) {
+        self.messages_poll.poll();
+        self.users_poll.poll();
+    }
+
+    // Swaps this account's parked state and credentials with the live ones.
+    fn swap(&mut self, app_state: &mut AppState, config: &mut Config) {
+        app_state.swap_account(&mut self.state);
+        std::mem::swap(&mut config.username, &mut self.username);
+        std::mem::swap(&mut config.password, &mut self.password);
+        std::mem::swap(&mut config.server_url, &mut self.server_url);
+    }
+}
+
+pub struct Accounts {
+    list: Vec<Account>,
+    active: usize,
+    client: reqwest::Client,
+    allow_plaintext: bool,
+}
+
+impl Accounts {
+    // The first account starts out active, with its state in `app_state` and credentials
+    // in `config`.
+    pub fn new(
+        name: String,
+        app_state: &mut AppState,
+        config: &mut Config,
+        client: reqwest::Client,
+        allow_plaintext: bool,
+    ) -> Self {
+        let mut first = Account::spawn(
+            name,
+            config.server_url.clone(),
+            client.clone(),
+            allow_plaintext,
+        );
+        app_state.read_state = std::mem::replace(&mut first.state.read_state, ReadState::new());
+        app_state.server_url = config.server_url.clone();
+        config.username = std::mem::take(&mut first.username);
+        config.password = std::mem::take(&mut first.password);
+
+        Self {
+            list: vec![first],
+            active: 0,
+            client,
+            allow_plaintext,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn poll(&mut self) {
+        for account in &mut self.list {
+            account.poll();
+        }
+    }
+
+    pub fn try_recv(&mut self, index: usize) -> Option<NetworkResponse> {
+        self.list[index].resp_rx.try_recv().ok()
+    }
+
+    pub fn active(&self) -> &Account {
+        &self.list[self.active]
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.list
+            .iter()
+            .position(|account| account.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn add(&mut self, name: String, server_url: String, app_state: &mut AppState) -> usize {
+        self.list.push(Account::spawn(
+            name,
+            server_url,
+            self.client.clone(),
+            self.allow_plaintext,
+        ));
+        self.update_label(app_state);
+        self.list.len() - 1
+    }
+
+    // Switches to the named account, first creating it when a server URL is given.
+    pub fn select(
+        &mut self,
+        name: String,
+        server_url: Option<String>,
+        app_state: &mut AppState,
+        config: &mut Config,
+    ) -> Result<(), CommandError> {
+        let index = match (self.find(&name), server_url) {
+            (Some(index), None) => index,
+            (Some(_), Some(_)) => return Err(CommandError::AccountExists(name)),
+            (None, Some(server_url)) => self.add(name, server_url, app_state),
+            (None, None) => return Err(CommandError::UnknownAccount(name)),
+        };
+        self.switch(index, app_state, config);
+        Ok(())
+    }
+
+    pub fn switch(&mut self, index: usize, app_state: &mut AppState, config: &mut Config) {
+        if index == self.active || index >= self.list.len() {
+            return;
+        }
+
+        // The input box is shared, so a half-finished edit can't follow us to another server.
+        if app_state.editing.is_some() {
+            app_state.clear_input();
+        }
+        app_state.pending_delete = None;
+        app_state.completion = None;
+
+        self.list[self.active].swap(app_state, config);
+        self.list[index].swap(app_state, config);
+        self.active = index;
+        self.update_label(app_state);
+    }
+
+    // Runs `f` against an account's state as if it were active, then puts everything back.
+    pub fn with_account<R>(
+        &mut self,
+        index: usize,
+        app_state: &mut AppState,
+        config: &mut Config,
+        f: impl FnOnce(&mut AppState, &mut Config, &mut Account) -> R,
+    ) -> R {
+        let account = &mut self.list[index];
+        if index == self.active {
+            return f(app_state, config, account);
+        }
+
+        account.swap(app_state, config);
+        app_state.background_account = Some(account.name.clone());
+        let result = f(app_state, config, account);
+        app_state.background_account = None;
+        account.swap(app_state, config);
+        result
+    }
+
+    // Unread counts across every account, the active one read from the live state.
+    pub fn total_unread(&self, app_state: &AppState) -> usize {
+        self.list
+            .iter()
+            .enumerate()
+            .map(|(index, account)| {
+                if index == self.active {
+                    app_state.total_unread()
+                } else {
+                    account.state.total_unread()
+                }
+            })
+            .sum()
+    }
+
+    pub fn summaries(&self, app_state: &AppState) -> Vec<AccountSummary> {
+        self.list
+            .iter()
+            .enumerate()
+            .map(|(index, account)| {
+                let state = if index == self.active {
+                    app_state
+                } else {
+                    &account.state
+                };
+                AccountSummary {
+                    name: account.name.clone(),
+                    server_url: state.server_url.clone(),
+                    connected: state.session_token.is_some(),
+                    unread: state.total_unread(),
+                }
+            })
+            .collect()
+    }
+
+    fn update_label(&self, app_state: &mut AppState) {
+        app_state.account_name = (self.list.len() > 1).then(|| self.active().name.clone());
+    }
+}
+
+pub struct AccountSummary {
+    pub name: String,
+    pub server_url: String,
+    pub connected: bool,
+    pub unread: usize,
+}
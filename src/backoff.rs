@@ -0,0 +1,54 @@
+use std::{
+    hash::{BuildHasher, RandomState},
+    time::Duration,
+};
+
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+// Exponential delay with "equal jitter": half of the delay is fixed and the
+// other half random, so many clients restarting together don't retry in sync.
+pub fn delay(attempt: u32) -> Duration {
+    let exponential = BASE_DELAY
+        .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+        .min(MAX_DELAY);
+    let half = exponential / 2;
+    half + half.mul_f64(jitter())
+}
+
+// A fresh RandomState is seeded from the OS, which is plenty for spreading retries.
+fn jitter() -> f64 {
+    let random = RandomState::new().hash_one(std::time::SystemTime::now());
+    (random % 1000) as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_doubles_within_the_jitter_bounds() {
+        for (attempt, expected) in [(0, 1), (1, 1), (2, 2), (3, 4), (6, 32)] {
+            let expected = Duration::from_secs(expected);
+            let delay = delay(attempt);
+            assert!(delay >= expected / 2, "attempt {attempt}: {delay:?}");
+            assert!(delay <= expected, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn delay_is_capped() {
+        for attempt in [7, 17, 40, u32::MAX] {
+            let delay = delay(attempt);
+            assert!(delay >= MAX_DELAY / 2, "attempt {attempt}: {delay:?}");
+            assert!(delay <= MAX_DELAY, "attempt {attempt}: {delay:?}");
+        }
+    }
+
+    #[test]
+    fn jitter_is_a_fraction() {
+        for _ in 0..100 {
+            assert!((0.0..1.0).contains(&jitter()));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, builder::FalseyValueParser};
+
+use crate::http::{self, ClientOptions};
+
+#[derive(Parser)]
+#[command(version, about = "A terminal client for the message server")]
+pub struct Args {
+    /// Also write logs to rotating files in the state directory
+    #[arg(long, env = "MESSAGE_CLIENT_LOG_FILE", value_parser = FalseyValueParser::new())]
+    pub log_file: bool,
+
+    /// Directory for log files [default: $XDG_STATE_HOME/message-client/logs]
+    #[arg(long, env = "MESSAGE_CLIENT_LOG_DIR", value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+
+    /// Start a new log file once the current one reaches this many megabytes
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_LOG_MAX_SIZE",
+        value_name = "MB",
+        default_value_t = 10
+    )]
+    pub log_max_size: u64,
+
+    /// Number of log files to keep before deleting the oldest
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_LOG_KEEP",
+        value_name = "FILES",
+        default_value_t = 7
+    )]
+    pub log_keep: usize,
+
+    /// Connect to an additional server as NAME=URL, can be repeated. The first one is shown
+    /// at startup.
+    #[arg(
+        long = "account",
+        env = "MESSAGE_CLIENT_ACCOUNTS",
+        value_name = "NAME=URL",
+        value_delimiter = ',',
+        value_parser = parse_account
+    )]
+    pub accounts: Vec<(String, String)>,
+
+    /// Allow logging in over unencrypted HTTP to hosts other than localhost
+    #[arg(long, env = "MESSAGE_CLIENT_ALLOW_PLAINTEXT", value_parser = FalseyValueParser::new())]
+    pub allow_plaintext: bool,
+
+    /// Seconds to wait for a connection to the server
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_CONNECT_TIMEOUT",
+        value_name = "SECS",
+        default_value_t = 10
+    )]
+    pub connect_timeout: u64,
+
+    /// Seconds to wait for a response before giving up on a request
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_REQUEST_TIMEOUT",
+        value_name = "SECS",
+        default_value_t = 30
+    )]
+    pub request_timeout: u64,
+
+    /// HTTP(S) proxy for all requests, e.g. http://proxy.example.com:3128
+    #[arg(long, env = "MESSAGE_CLIENT_PROXY", value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// PEM file with extra root certificates to trust, can be repeated
+    #[arg(
+        long = "ca-cert",
+        env = "MESSAGE_CLIENT_CA_CERTS",
+        value_name = "FILE",
+        value_delimiter = ','
+    )]
+    pub ca_certs: Vec<PathBuf>,
+
+    /// PEM client certificate for servers that require TLS client authentication
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_CLIENT_CERT",
+        value_name = "FILE",
+        requires = "client_key"
+    )]
+    pub client_cert: Option<PathBuf>,
+
+    /// PKCS#8 PEM private key for --client-cert
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_CLIENT_KEY",
+        value_name = "FILE",
+        requires = "client_cert"
+    )]
+    pub client_key: Option<PathBuf>,
+
+    /// User-Agent header sent with every request
+    #[arg(
+        long,
+        env = "MESSAGE_CLIENT_USER_AGENT",
+        value_name = "AGENT",
+        default_value = http::USER_AGENT
+    )]
+    pub user_agent: String,
+}
+
+impl Args {
+    pub fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            request_timeout: Duration::from_secs(self.request_timeout),
+            proxy: self.proxy.clone(),
+            ca_certs: self.ca_certs.clone(),
+            client_cert: self.client_cert.clone(),
+            client_key: self.client_key.clone(),
+            user_agent: self.user_agent.clone(),
+        }
+    }
+}
+
+fn parse_account(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, url)) if !name.trim().is_empty() && !url.trim().is_empty() => Ok((
+            name.trim().to_string(),
+            url.trim().trim_end_matches('/').to_string(),
+        )),
+        _ => Err("expected NAME=URL".to_string()),
+    }
+}
@@ -0,0 +1,387 @@
+use std::{ops::ControlFlow, path::PathBuf};
+
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use crate::{
+    components::{Channel, Config},
+    network::NetworkRequest,
+    state::{AppState, Upload},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    None,
+    Channel,
+    Text,
+}
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub description: &'static str,
+    pub arg: ArgKind,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "join",
+        usage: "/join <#channel|@user>",
+        description: "Switch to a channel or direct conversation",
+        arg: ArgKind::Channel,
+    },
+    CommandSpec {
+        name: "nick",
+        usage: "/nick <name>",
+        description: "Change your display name",
+        arg: ArgKind::Text,
+    },
+    CommandSpec {
+        name: "me",
+        usage: "/me <action>",
+        description: "Send an action message",
+        arg: ArgKind::Text,
+    },
+    CommandSpec {
+        name: "upload",
+        usage: "/upload <path>",
+        description: "Attach a file to this channel",
+        arg: ArgKind::Text,
+    },
+    CommandSpec {
+        name: "clear",
+        usage: "/clear",
+        description: "Clear the messages shown in this channel",
+        arg: ArgKind::None,
+    },
+    CommandSpec {
+        name: "reconnect",
+        usage: "/reconnect",
+        description: "Drop the session and log in again",
+        arg: ArgKind::None,
+    },
+    CommandSpec {
+        name: "server",
+        usage: "/server <url>",
+        description: "Connect to a different server",
+        arg: ArgKind::Text,
+    },
+    CommandSpec {
+        name: "login",
+        usage: "/login",
+        description: "Log in, or change user or server",
+        arg: ArgKind::None,
+    },
+    CommandSpec {
+        name: "register",
+        usage: "/register",
+        description: "Create a new user on this server",
+        arg: ArgKind::None,
+    },
+    CommandSpec {
+        name: "account",
+        usage: "/account <name> [url]",
+        description: "Switch to an account, or add one for the server at url",
+        arg: ArgKind::Text,
+    },
+    CommandSpec {
+        name: "help",
+        usage: "/help",
+        description: "List available commands",
+        arg: ArgKind::None,
+    },
+    CommandSpec {
+        name: "quit",
+        usage: "/quit",
+        description: "Exit the client",
+        arg: ArgKind::None,
+    },
+];
+
+pub enum Command {
+    Join(String),
+    Nick(String),
+    Me(String),
+    Upload(String),
+    Clear,
+    Reconnect,
+    Server(String),
+    Login,
+    Register,
+    Account {
+        name: String,
+        server_url: Option<String>,
+    },
+    Help,
+    Quit,
+}
+
+// Things a command needs from the main loop, beyond the state it's given.
+pub enum Action {
+    Quit,
+    Login,
+    Register,
+    Account {
+        name: String,
+        server_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("unknown command /{0}, type /help for a list of commands")]
+    Unknown(String),
+    #[error("usage: {0}")]
+    Usage(&'static str),
+    #[error("no channel or user named {0}")]
+    UnknownChannel(String),
+    #[error("you must be connected to use /{0}")]
+    NotConnected(&'static str),
+    #[error("cannot upload {0}: {1}")]
+    File(String, std::io::Error),
+    #[error("an upload is already in progress")]
+    UploadInProgress,
+    #[error("no account named {0}, add one with /account {0} <url>")]
+    UnknownAccount(String),
+    #[error("an account named {0} already exists")]
+    AccountExists(String),
+}
+
+pub fn find(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+pub fn parse(line: &str) -> Option<Result<Command, CommandError>> {
+    let line = line.trim();
+    let rest = line.strip_prefix('/')?;
+    let (name, args) = match rest.split_once(char::is_whitespace) {
+        Some((name, args)) => (name, args.trim()),
+        None => (rest, ""),
+    };
+
+    let Some(spec) = find(name) else {
+        return Some(Err(CommandError::Unknown(name.to_string())));
+    };
+    if spec.arg != ArgKind::None && args.is_empty() {
+        return Some(Err(CommandError::Usage(spec.usage)));
+    }
+
+    let command = match spec.name {
+        "join" => Command::Join(args.to_string()),
+        "nick" => Command::Nick(args.to_string()),
+        "me" => Command::Me(args.to_string()),
+        "upload" => Command::Upload(args.to_string()),
+        "clear" => Command::Clear,
+        "reconnect" => Command::Reconnect,
+        "server" => Command::Server(args.to_string()),
+        "login" => Command::Login,
+        "register" => Command::Register,
+        "account" => match args.split_once(char::is_whitespace) {
+            Some((name, url)) => Command::Account {
+                name: name.to_string(),
+                server_url: Some(url.trim().trim_end_matches('/').to_string()),
+            },
+            None => Command::Account {
+                name: args.to_string(),
+                server_url: None,
+            },
+        },
+        "help" => Command::Help,
+        "quit" => Command::Quit,
+        _ => return Some(Err(CommandError::Unknown(name.to_string()))),
+    };
+
+    Some(Ok(command))
+}
+
+pub fn execute(
+    command: Command,
+    state: &mut AppState,
+    config: &mut Config,
+    req_tx: &mpsc::UnboundedSender<NetworkRequest>,
+) -> Result<ControlFlow<Action>, CommandError> {
+    match command {
+        Command::Join(target) => {
+            let channel = match target.strip_prefix('@') {
+                Some(name) => {
+                    let user = state
+                        .users
+                        .iter()
+                        .find(|u| u.name.eq_ignore_ascii_case(name))
+                        .ok_or(CommandError::UnknownChannel(target.clone()))?;
+                    Channel::Direct(user.id)
+                }
+                None => Channel::Public(target.trim_start_matches('#').to_string()),
+            };
+            match channel {
+                Channel::Direct(user_id) => state.open_direct(user_id),
+                channel => state.switch_channel(channel),
+            }
+        }
+        Command::Nick(name) => {
+            let session = state
+                .session_token
+                .clone()
+                .ok_or(CommandError::NotConnected("nick"))?;
+            req_tx
+                .send(NetworkRequest::UpdateProfile { name, session })
+                .ok();
+        }
+        Command::Me(action) => {
+            let request = state
+                .send_content(format!("/me {action}"))
+                .ok_or(CommandError::NotConnected("me"))?;
+            req_tx.send(request).ok();
+        }
+        Command::Upload(path) => {
+            let session = state
+                .session_token
+                .clone()
+                .ok_or(CommandError::NotConnected("upload"))?;
+            if state.upload.is_some() {
+                return Err(CommandError::UploadInProgress);
+            }
+
+            let path = match path.strip_prefix("~/").zip(dirs::home_dir()) {
+                Some((rest, home)) => home.join(rest),
+                None => PathBuf::from(&path),
+            };
+            let metadata = std::fs::metadata(&path)
+                .map_err(|e| CommandError::File(path.display().to_string(), e))?;
+            state.upload = Some(Upload {
+                filename: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                sent: 0,
+                total: metadata.len(),
+            });
+            req_tx
+                .send(NetworkRequest::UploadAttachment {
+                    path,
+                    channel: state.active_channel.clone(),
+                    session,
+                })
+                .ok();
+        }
+        Command::Clear => state.clear_channel(),
+        Command::Reconnect => {
+            state.retry_connection();
+        }
+        Command::Server(url) => {
+            config.server_url = url.trim_end_matches('/').to_string();
+            state.read_state.set_server(&config.server_url);
+            state.server_url = config.server_url.clone();
+            req_tx
+                .send(NetworkRequest::SetServer(config.server_url.clone()))
+                .ok();
+            state.system_message(format!("Switching to {}", config.server_url));
+            state.pending_requests.clear();
+            state.loaded_public = false;
+            state.loaded_direct = false;
+            state.retry_connection();
+        }
+        Command::Help => {
+            for spec in COMMANDS {
+                state.system_message(format!("{:<26} {}", spec.usage, spec.description));
+            }
+        }
+        Command::Login => return Ok(ControlFlow::Break(Action::Login)),
+        Command::Register => return Ok(ControlFlow::Break(Action::Register)),
+        Command::Account { name, server_url } => {
+            return Ok(ControlFlow::Break(Action::Account { name, server_url }));
+        }
+        Command::Quit => return Ok(ControlFlow::Break(Action::Quit)),
+    }
+
+    Ok(ControlFlow::Continue(()))
+}
+
+pub fn candidates(state: &AppState, input: &str, start: usize, token: &str) -> Vec<String> {
+    if start == 0 {
+        let Some(prefix) = token.strip_prefix('/') else {
+            return Vec::new();
+        };
+        return COMMANDS
+            .iter()
+            .filter(|spec| spec.name.starts_with(prefix))
+            .map(|spec| format!("/{}", spec.name))
+            .collect();
+    }
+
+    let name = input[1..].split_whitespace().next().unwrap_or_default();
+    match find(name).map(|spec| spec.arg) {
+        Some(ArgKind::Channel) => {
+            let token = token.to_lowercase();
+            state
+                .channels()
+                .iter()
+                .map(|channel| state.channel_name(channel))
+                .chain(state.users.iter().map(|u| format!("@{}", u.name)))
+                .filter(|name| {
+                    let name = name.to_lowercase();
+                    name.starts_with(&token) || name[1..].starts_with(&token)
+                })
+                .fold(Vec::new(), |mut names, name| {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                    names
+                })
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_plain_messages() {
+        assert!(parse("hello /join").is_none());
+        assert!(parse("").is_none());
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert!(matches!(
+            parse("  /join   #general  "),
+            Some(Ok(Command::Join(target))) if target == "#general"
+        ));
+        assert!(matches!(
+            parse("/me waves  hello"),
+            Some(Ok(Command::Me(action))) if action == "waves  hello"
+        ));
+        assert!(matches!(parse("/quit"), Some(Ok(Command::Quit))));
+    }
+
+    #[test]
+    fn parses_account_with_and_without_url() {
+        assert!(matches!(
+            parse("/account work https://chat.example.com/ "),
+            Some(Ok(Command::Account { name, server_url: Some(url) }))
+                if name == "work" && url == "https://chat.example.com"
+        ));
+        assert!(matches!(
+            parse("/account work"),
+            Some(Ok(Command::Account { name, server_url: None })) if name == "work"
+        ));
+    }
+
+    #[test]
+    fn reports_unknown_commands_and_missing_arguments() {
+        assert!(matches!(
+            parse("/frobnicate now"),
+            Some(Err(CommandError::Unknown(name))) if name == "frobnicate"
+        ));
+        assert!(matches!(
+            parse("/join"),
+            Some(Err(CommandError::Usage("/join <#channel|@user>")))
+        ));
+        assert!(matches!(
+            parse("/nick   "),
+            Some(Err(CommandError::Usage(_)))
+        ));
+    }
+}
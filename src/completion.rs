@@ -0,0 +1,53 @@
+pub struct Completion {
+    pub start: usize,
+    pub end: usize,
+    pub candidates: Vec<String>,
+    pub selected: usize,
+}
+
+impl Completion {
+    pub fn new(start: usize, end: usize, candidates: Vec<String>) -> Option<Self> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            start,
+            end,
+            candidates,
+            selected: 0,
+        })
+    }
+
+    pub fn current(&self) -> &str {
+        &self.candidates[self.selected]
+    }
+
+    pub fn advance(&mut self, replacement_len: usize) {
+        self.end = self.start + replacement_len;
+        self.selected = (self.selected + 1) % self.candidates.len();
+    }
+}
+
+pub fn token_before(input: &str, cursor: usize) -> (usize, &str) {
+    let before = &input[..cursor];
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map(|(index, c)| index + c.len_utf8())
+        .unwrap_or(0);
+
+    (start, &before[start..])
+}
+
+pub fn mention_candidates<'a>(prefix: &str, names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let prefix = prefix.to_lowercase();
+    let mut candidates: Vec<String> = names
+        .filter(|name| name.to_lowercase().starts_with(&prefix))
+        .map(|name| format!("@{name}"))
+        .collect();
+    candidates.sort_by_key(|c| c.to_lowercase());
+    candidates.dedup();
+    candidates
+}
@@ -0,0 +1,143 @@
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::account::AccountSummary;
+
+pub struct AccountPicker {
+    accounts: Vec<AccountSummary>,
+    selected: usize,
+    is_visible: bool,
+}
+
+impl AccountPicker {
+    pub fn new() -> Self {
+        Self {
+            accounts: Vec::new(),
+            selected: 0,
+            is_visible: false,
+        }
+    }
+
+    pub fn open(&mut self, accounts: Vec<AccountSummary>, active: usize) {
+        self.accounts = accounts;
+        self.selected = active;
+        self.is_visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.is_visible = false;
+    }
+
+    pub fn next(&mut self) {
+        if !self.accounts.is_empty() {
+            self.selected = (self.selected + 1) % self.accounts.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.accounts.is_empty() {
+            self.selected = (self.selected + self.accounts.len() - 1) % self.accounts.len();
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.is_visible {
+            return;
+        }
+
+        let size = f.size();
+
+        let popup_width = size.width.saturating_sub(10).min(80);
+        let popup_height = (self.accounts.len() as u16 + 4).min(size.height);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let mut lines: Vec<Spans> = self
+            .accounts
+            .iter()
+            .enumerate()
+            .map(|(index, account)| {
+                let name_style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let (status, status_color) = if account.connected {
+                    ("●", Color::Green)
+                } else {
+                    ("○", Color::Red)
+                };
+
+                let mut spans = vec![
+                    Span::styled(format!("{status} "), Style::default().fg(status_color)),
+                    Span::styled(format!(" {} ", account.name), name_style),
+                    Span::styled(
+                        format!(" {}", account.server_url),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if account.unread > 0 {
+                    spans.push(Span::styled(
+                        format!(" ({} unread)", account.unread),
+                        Style::default().fg(Color::Yellow),
+                    ));
+                }
+                Spans::from(spans)
+            })
+            .collect();
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(vec![
+            Span::styled(
+                "Enter",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Switch  "),
+            Span::styled(
+                "Esc",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Close  "),
+            Span::styled(
+                "/account <name> <url>",
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(": Add"),
+        ]));
+
+        let block = Block::default()
+            .title(" Accounts ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(Paragraph::new(Text::from(lines)).block(block), popup_area);
+    }
+}
@@ -0,0 +1,94 @@
+use std::{cell::RefCell, rc::Rc};
+
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::state::AppState;
+
+pub struct ChannelList {
+    app_state: Rc<RefCell<AppState>>,
+}
+
+impl ChannelList {
+    pub fn new(app_state: Rc<RefCell<AppState>>) -> Self {
+        Self { app_state }
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let state = self.app_state.borrow();
+        let mut text_lines = Vec::new();
+        let mut in_direct = false;
+
+        for channel in state.channels() {
+            if channel.is_direct() && !in_direct {
+                in_direct = true;
+                text_lines.push(Spans::from(""));
+                text_lines.push(Spans::from(Span::styled(
+                    "Direct Messages",
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::BOLD),
+                )));
+            }
+
+            let is_active = channel == state.active_channel;
+            let unread = if is_active {
+                0
+            } else {
+                state.unread_count(&channel)
+            };
+            let mentions = if is_active {
+                0
+            } else {
+                state.mention_count(&channel)
+            };
+
+            let name_style = if is_active {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if unread > 0 {
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+
+            let mut spans = vec![Span::styled(state.channel_name(&channel), name_style)];
+            if unread > 0 {
+                spans.push(Span::styled(
+                    format!(" ({unread})"),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if mentions > 0 {
+                spans.push(Span::styled(
+                    format!(" @{mentions}"),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            text_lines.push(Spans::from(spans));
+        }
+
+        let block = Block::default()
+            .title("Channels")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Blue));
+
+        let paragraph = Paragraph::new(Text::from(text_lines)).block(block);
+
+        f.render_widget(paragraph, area);
+    }
+}
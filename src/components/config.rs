@@ -1,25 +1,67 @@
 use tui::{
     Frame,
     backend::Backend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Span, Spans},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
 };
 
+use crate::http;
+
+use super::form::{Form, FormField};
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ConfigField {
     Username,
     Password,
     ServerUrl,
+    MutedChannels,
+    LinkOpener,
+    DownloadDir,
 }
 
+const FIELDS: &[FormField<ConfigField>] = &[
+    FormField {
+        id: ConfigField::Username,
+        label: "Username",
+        masked: false,
+    },
+    FormField {
+        id: ConfigField::Password,
+        label: "Password",
+        masked: true,
+    },
+    FormField {
+        id: ConfigField::ServerUrl,
+        label: "Server URL",
+        masked: false,
+    },
+    FormField {
+        id: ConfigField::MutedChannels,
+        label: "Muted channels (comma separated)",
+        masked: false,
+    },
+    FormField {
+        id: ConfigField::LinkOpener,
+        label: "Link opener command",
+        masked: false,
+    },
+    FormField {
+        id: ConfigField::DownloadDir,
+        label: "Download directory",
+        masked: false,
+    },
+];
+
 pub struct Config {
     pub username: String,
     pub password: String,
     pub server_url: String,
-    focused_field: ConfigField,
-    cursor_position: usize,
+    pub muted_channels: String,
+    pub link_opener: String,
+    pub download_dir: String,
+    form: Form<ConfigField>,
     is_visible: bool,
 }
 
@@ -28,78 +70,77 @@ impl Config {
         Self {
             username: String::new(),
             password: String::new(),
-            server_url: "http://ec2-44-250-68-143.us-west-2.compute.amazonaws.com:8000".to_string(),
-            focused_field: ConfigField::Username,
-            cursor_position: 0,
+            server_url: http::default_server_url(),
+            muted_channels: String::new(),
+            link_opener: if cfg!(target_os = "macos") {
+                "open".to_string()
+            } else {
+                "xdg-open".to_string()
+            },
+            download_dir: dirs::download_dir()
+                .or_else(|| dirs::home_dir().map(|home| home.join("Downloads")))
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "Downloads".to_string()),
+            form: Form::new(FIELDS),
             is_visible: false,
         }
     }
 
     pub fn next_field(&mut self) {
-        self.focused_field = match self.focused_field {
-            ConfigField::Username => ConfigField::Password,
-            ConfigField::Password => ConfigField::ServerUrl,
-            ConfigField::ServerUrl => ConfigField::Username,
-        };
-        self.cursor_position = self.get_field().len();
+        self.form.next_field();
+        let (form, value) = self.focused_value();
+        form.cursor_to_end(value);
     }
 
     pub fn previous_field(&mut self) {
-        self.focused_field = match self.focused_field {
-            ConfigField::Username => ConfigField::ServerUrl,
-            ConfigField::Password => ConfigField::Username,
-            ConfigField::ServerUrl => ConfigField::Password,
-        };
-        self.cursor_position = self.get_field().len();
+        self.form.previous_field();
+        let (form, value) = self.focused_value();
+        form.cursor_to_end(value);
     }
 
     pub fn insert_char(&mut self, c: char) {
-        let pos = self.cursor_position;
-        let field = self.get_field_mut();
-        field.insert(pos, c);
-        self.cursor_position += 1;
+        let (form, value) = self.focused_value();
+        form.insert_char(value, c);
     }
 
     pub fn delete_char(&mut self) {
-        let pos = self.cursor_position;
-        let field = self.get_field_mut();
-        if pos < field.len() {
-            field.remove(pos);
-        }
+        let (form, value) = self.focused_value();
+        form.delete_char(value);
     }
 
     pub fn backspace(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-            let pos = self.cursor_position;
-            let field = self.get_field_mut();
-            field.remove(pos);
-        }
+        let (form, value) = self.focused_value();
+        form.backspace(value);
     }
 
     pub fn move_cursor_left(&mut self) {
-        self.cursor_position = self.cursor_position.saturating_sub(1);
+        let (form, value) = self.focused_value();
+        form.move_cursor_left(value);
     }
 
     pub fn move_cursor_right(&mut self) {
-        let max_pos = self.get_field().len();
-        self.cursor_position = (self.cursor_position + 1).min(max_pos);
+        let (form, value) = self.focused_value();
+        form.move_cursor_right(value);
     }
 
-    fn get_field(&self) -> &String {
-        match self.focused_field {
-            ConfigField::Username => &self.username,
-            ConfigField::Password => &self.password,
-            ConfigField::ServerUrl => &self.server_url,
-        }
-    }
-
-    fn get_field_mut(&mut self) -> &mut String {
-        match self.focused_field {
+    fn focused_value(&mut self) -> (&mut Form<ConfigField>, &mut String) {
+        let value = match self.form.focused() {
             ConfigField::Username => &mut self.username,
             ConfigField::Password => &mut self.password,
             ConfigField::ServerUrl => &mut self.server_url,
-        }
+            ConfigField::MutedChannels => &mut self.muted_channels,
+            ConfigField::LinkOpener => &mut self.link_opener,
+            ConfigField::DownloadDir => &mut self.download_dir,
+        };
+        (&mut self.form, value)
+    }
+
+    pub fn is_muted(&self, channel_name: &str) -> bool {
+        let channel_name = channel_name.trim_start_matches('#');
+        self.muted_channels
+            .split(',')
+            .map(|c| c.trim().trim_start_matches('#'))
+            .any(|c| !c.is_empty() && c.eq_ignore_ascii_case(channel_name))
     }
 
     pub fn open(&mut self) {
@@ -118,7 +159,7 @@ impl Config {
         let size = f.size();
 
         let popup_width = size.width.saturating_sub(10).min(70);
-        let popup_height = 15;
+        let popup_height = 24.min(size.height);
         let popup_x = (size.width.saturating_sub(popup_width)) / 2;
         let popup_y = (size.height.saturating_sub(popup_height)) / 2;
 
@@ -151,44 +192,17 @@ impl Config {
             height: popup_area.height.saturating_sub(4),
         };
 
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Username field
-                Constraint::Length(3), // Password field
-                Constraint::Length(3), // Server URL field
-                Constraint::Min(1),    // Help text
-            ])
-            .split(inner_area);
-
-        self.render_field(
-            f,
-            chunks[0],
-            "Username",
-            &self.username,
-            ConfigField::Username,
-            false,
-            self.cursor_position,
-        );
-
-        self.render_field(
+        let help_area = self.form.render(
             f,
-            chunks[1],
-            "Password",
-            &self.password,
-            ConfigField::Password,
-            true,
-            self.cursor_position,
-        );
-
-        self.render_field(
-            f,
-            chunks[2],
-            "Server URL",
-            &self.server_url,
-            ConfigField::ServerUrl,
-            false,
-            self.cursor_position,
+            inner_area,
+            &[
+                &self.username,
+                &self.password,
+                &self.server_url,
+                &self.muted_channels,
+                &self.link_opener,
+                &self.download_dir,
+            ],
         );
 
         let help_text = vec![
@@ -215,83 +229,6 @@ impl Config {
             .alignment(Alignment::Center)
             .wrap(Wrap { trim: true });
 
-        f.render_widget(help_paragraph, chunks[3]);
-    }
-
-    fn render_field<B: Backend>(
-        &self,
-        f: &mut Frame<B>,
-        area: Rect,
-        label: &str,
-        value: &str,
-        field: ConfigField,
-        mask: bool,
-        cursor_pos: usize,
-    ) {
-        let is_focused = self.focused_field == field;
-
-        let border_style = if is_focused {
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(Color::DarkGray)
-        };
-
-        let display_value = if mask {
-            "*".repeat(value.len())
-        } else {
-            value.to_string()
-        };
-
-        let text = if is_focused {
-            let chars: Vec<char> = display_value.chars().collect();
-            if chars.is_empty() || cursor_pos >= chars.len() {
-                let before: String = chars.iter().collect();
-                Spans::from(vec![
-                    Span::styled(before, Style::default().fg(Color::White)),
-                    Span::styled(" ", Style::default().fg(Color::Black).bg(Color::White)),
-                ])
-            } else {
-                let before: String = chars[..cursor_pos].iter().collect();
-                let at_cursor = chars[cursor_pos];
-                let after: String = chars[cursor_pos + 1..].iter().collect();
-                Spans::from(vec![
-                    Span::styled(before, Style::default().fg(Color::White)),
-                    Span::styled(
-                        at_cursor.to_string(),
-                        Style::default().fg(Color::Black).bg(Color::White),
-                    ),
-                    Span::styled(after, Style::default().fg(Color::White)),
-                ])
-            }
-        } else if display_value.is_empty() {
-            Spans::from(vec![Span::styled(
-                "...",
-                Style::default()
-                    .fg(Color::DarkGray)
-                    .add_modifier(Modifier::ITALIC),
-            )])
-        } else {
-            Spans::from(vec![Span::styled(
-                display_value,
-                Style::default().fg(Color::White),
-            )])
-        };
-
-        let title = if is_focused {
-            format!(" {} (editing) ", label)
-        } else {
-            format!(" {} ", label)
-        };
-
-        let block = Block::default()
-            .title(title)
-            .borders(Borders::ALL)
-            .border_style(border_style);
-
-        let paragraph = Paragraph::new(text).block(block);
-
-        f.render_widget(paragraph, area);
+        f.render_widget(help_paragraph, help_area);
     }
 }
@@ -0,0 +1,175 @@
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::emoji;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmojiTarget {
+    Input,
+    Reaction,
+}
+
+pub struct EmojiPicker {
+    target: EmojiTarget,
+    query: String,
+    matches: Vec<(&'static str, &'static str)>,
+    selected: usize,
+    is_visible: bool,
+}
+
+impl EmojiPicker {
+    pub fn new() -> Self {
+        Self {
+            target: EmojiTarget::Input,
+            query: String::new(),
+            matches: emoji::search(""),
+            selected: 0,
+            is_visible: false,
+        }
+    }
+
+    pub fn open(&mut self, target: EmojiTarget) {
+        self.target = target;
+        self.query.clear();
+        self.update_matches();
+        self.is_visible = true;
+    }
+
+    pub fn target(&self) -> EmojiTarget {
+        self.target
+    }
+
+    pub fn close(&mut self) {
+        self.is_visible = false;
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_matches();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.update_matches();
+    }
+
+    pub fn next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&'static str> {
+        self.matches.get(self.selected).map(|(_, emoji)| *emoji)
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = emoji::search(&self.query);
+        self.selected = 0;
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.is_visible {
+            return;
+        }
+
+        let size = f.size();
+
+        let popup_width = 40.min(size.width);
+        let popup_height = 16.min(size.height);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let mut lines = vec![
+            Spans::from(vec![
+                Span::styled("Search: ", Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(":{}", self.query),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(" ", Style::default().bg(Color::White)),
+            ]),
+            Spans::from(""),
+        ];
+
+        // Keep the selection in view by scrolling the list a page at a time.
+        let visible = popup_height.saturating_sub(6) as usize;
+        let first = self
+            .selected
+            .checked_div(visible)
+            .map_or(0, |page| page * visible);
+        if self.matches.is_empty() {
+            lines.push(Spans::from(Span::styled(
+                "No matching emoji",
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )));
+        }
+        for (index, (name, emoji)) in self.matches.iter().enumerate().skip(first).take(visible) {
+            let style = if index == self.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Spans::from(vec![
+                Span::raw(format!(" {emoji} ")),
+                Span::styled(format!(":{name}:"), style),
+            ]));
+        }
+
+        while lines.len() < popup_height.saturating_sub(3) as usize {
+            lines.push(Spans::from(""));
+        }
+        lines.push(Spans::from(vec![
+            Span::styled(
+                "Enter",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Pick  "),
+            Span::styled(
+                "Esc",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Close"),
+        ]));
+
+        let block = Block::default()
+            .title(" Emoji ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(Paragraph::new(Text::from(lines)).block(block), popup_area);
+    }
+}
@@ -0,0 +1,207 @@
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::text;
+
+const FIELD_HEIGHT: u16 = 3;
+
+pub struct FormField<F> {
+    pub id: F,
+    pub label: &'static str,
+    pub masked: bool,
+}
+
+// Focus and cursor handling for a column of text fields. The values themselves stay with
+// the owner, which hands in the focused one when editing.
+pub struct Form<F: 'static> {
+    fields: &'static [FormField<F>],
+    focused: usize,
+    cursor_position: usize,
+    // Set while focus is on something outside the form, so no field is highlighted.
+    blurred: bool,
+}
+
+impl<F: Copy + PartialEq> Form<F> {
+    pub fn new(fields: &'static [FormField<F>]) -> Self {
+        Self {
+            fields,
+            focused: 0,
+            cursor_position: 0,
+            blurred: false,
+        }
+    }
+
+    pub fn focused(&self) -> F {
+        self.fields[self.focused].id
+    }
+
+    pub fn focus(&mut self, field: F, value: &str) {
+        if let Some(index) = self.fields.iter().position(|f| f.id == field) {
+            self.focused = index;
+            self.cursor_position = value.len();
+            self.blurred = false;
+        }
+    }
+
+    pub fn blur(&mut self) {
+        self.blurred = true;
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.focused == self.fields.len() - 1
+    }
+
+    pub fn is_first(&self) -> bool {
+        self.focused == 0
+    }
+
+    pub fn next_field(&mut self) {
+        self.focused = (self.focused + 1) % self.fields.len();
+    }
+
+    pub fn previous_field(&mut self) {
+        self.focused = (self.focused + self.fields.len() - 1) % self.fields.len();
+    }
+
+    pub fn cursor_to_end(&mut self, value: &str) {
+        self.cursor_position = value.len();
+    }
+
+    pub fn insert_char(&mut self, value: &mut String, c: char) {
+        let pos = self.cursor_position.min(value.len());
+        value.insert(pos, c);
+        self.cursor_position = pos + c.len_utf8();
+    }
+
+    pub fn delete_char(&mut self, value: &mut String) {
+        let pos = self.cursor_position;
+        if pos < value.len() {
+            value.remove(pos);
+        }
+    }
+
+    pub fn backspace(&mut self, value: &mut String) {
+        if self.cursor_position > 0 {
+            self.cursor_position = text::previous_boundary(value, self.cursor_position);
+            value.remove(self.cursor_position);
+        }
+    }
+
+    pub fn move_cursor_left(&mut self, value: &str) {
+        self.cursor_position = text::previous_boundary(value, self.cursor_position);
+    }
+
+    pub fn move_cursor_right(&mut self, value: &str) {
+        self.cursor_position = text::next_boundary(value, self.cursor_position);
+    }
+
+    // Lays the fields out top to bottom and returns the space left below them. When the
+    // area is too short for all of them, only a page of fields around the focused one is shown.
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, values: &[&str]) -> Rect {
+        let shown = (area.height / FIELD_HEIGHT).min(self.fields.len() as u16) as usize;
+        let first = self
+            .focused
+            .checked_div(shown)
+            .map_or(0, |page| page * shown)
+            .min(self.fields.len() - shown);
+
+        let mut constraints = vec![Constraint::Length(FIELD_HEIGHT); shown];
+        constraints.push(Constraint::Min(0));
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        let fields = self.fields.iter().zip(values).enumerate();
+        for (chunk, (index, (field, value))) in chunks.iter().zip(fields.skip(first).take(shown)) {
+            let is_focused = !self.blurred && index == self.focused;
+            self.render_field(f, *chunk, field, value, is_focused);
+        }
+        chunks[shown]
+    }
+
+    fn render_field<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        field: &FormField<F>,
+        value: &str,
+        is_focused: bool,
+    ) {
+        let cursor_pos = value
+            .get(..self.cursor_position)
+            .unwrap_or(value)
+            .chars()
+            .count();
+
+        let border_style = if is_focused {
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+
+        let display_value = if field.masked {
+            "*".repeat(value.chars().count())
+        } else {
+            value.to_string()
+        };
+
+        let text = if is_focused {
+            let chars: Vec<char> = display_value.chars().collect();
+            if chars.is_empty() || cursor_pos >= chars.len() {
+                let before: String = chars.iter().collect();
+                Spans::from(vec![
+                    Span::styled(before, Style::default().fg(Color::White)),
+                    Span::styled(" ", Style::default().fg(Color::Black).bg(Color::White)),
+                ])
+            } else {
+                let before: String = chars[..cursor_pos].iter().collect();
+                let at_cursor = chars[cursor_pos];
+                let after: String = chars[cursor_pos + 1..].iter().collect();
+                Spans::from(vec![
+                    Span::styled(before, Style::default().fg(Color::White)),
+                    Span::styled(
+                        at_cursor.to_string(),
+                        Style::default().fg(Color::Black).bg(Color::White),
+                    ),
+                    Span::styled(after, Style::default().fg(Color::White)),
+                ])
+            }
+        } else if display_value.is_empty() {
+            Spans::from(vec![Span::styled(
+                "...",
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )])
+        } else {
+            Spans::from(vec![Span::styled(
+                display_value,
+                Style::default().fg(Color::White),
+            )])
+        };
+
+        let title = if is_focused {
+            format!(" {} (editing) ", field.label)
+        } else {
+            format!(" {} ", field.label)
+        };
+
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(border_style);
+
+        let paragraph = Paragraph::new(text).block(block);
+
+        f.render_widget(paragraph, area);
+    }
+}
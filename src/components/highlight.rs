@@ -0,0 +1,128 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{self, Theme, ThemeSet},
+    parsing::SyntaxSet,
+};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+const MAX_CACHED_BLOCKS: usize = 256;
+
+pub type HighlightedLine = Vec<Span<'static>>;
+
+pub struct Highlighter {
+    syntaxes: SyntaxSet,
+    theme: Theme,
+    cache: RefCell<HashMap<u64, Vec<HighlightedLine>>>,
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        let mut themes = ThemeSet::load_defaults();
+        let theme = themes
+            .themes
+            .remove("base16-ocean.dark")
+            .unwrap_or_default();
+
+        Self {
+            syntaxes: SyntaxSet::load_defaults_newlines(),
+            theme,
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn highlight(&self, lang: &str, lines: &[&str]) -> Option<Vec<HighlightedLine>> {
+        let syntax = self.syntaxes.find_syntax_by_token(lang)?;
+
+        let mut hasher = DefaultHasher::new();
+        (lang, lines).hash(&mut hasher);
+        let key = hasher.finish();
+        if let Some(cached) = self.cache.borrow().get(&key) {
+            return Some(cached.clone());
+        }
+
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+        let mut highlighted = Vec::with_capacity(lines.len());
+        for line in lines {
+            let line = format!("{line}\n");
+            let regions = highlighter.highlight_line(&line, &self.syntaxes).ok()?;
+            highlighted.push(
+                regions
+                    .into_iter()
+                    .map(|(style, text)| {
+                        Span::styled(text.trim_end_matches('\n').to_string(), to_tui_style(style))
+                    })
+                    .filter(|span| !span.content.is_empty())
+                    .collect(),
+            );
+        }
+
+        let mut cache = self.cache.borrow_mut();
+        if cache.len() >= MAX_CACHED_BLOCKS {
+            cache.clear();
+        }
+        cache.insert(key, highlighted.clone());
+
+        Some(highlighted)
+    }
+}
+
+fn to_tui_style(style: highlighting::Style) -> Style {
+    let mut tui_style = Style::default().fg(nearest_color(style.foreground));
+    if style.font_style.contains(highlighting::FontStyle::BOLD) {
+        tui_style = tui_style.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(highlighting::FontStyle::ITALIC) {
+        tui_style = tui_style.add_modifier(Modifier::ITALIC);
+    }
+    tui_style
+}
+
+fn nearest_color(color: highlighting::Color) -> Color {
+    let (r, g, b) = (
+        f32::from(color.r) / 255.0,
+        f32::from(color.g) / 255.0,
+        f32::from(color.b) / 255.0,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+
+    if chroma < 0.12 {
+        return match max {
+            m if m > 0.75 => Color::White,
+            m if m > 0.45 => Color::Gray,
+            _ => Color::DarkGray,
+        };
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let light = max > 0.8;
+
+    match hue {
+        h if !(20.0..330.0).contains(&h) => pick(light, Color::LightRed, Color::Red),
+        h if h < 70.0 => pick(light, Color::LightYellow, Color::Yellow),
+        h if h < 165.0 => pick(light, Color::LightGreen, Color::Green),
+        h if h < 200.0 => pick(light, Color::LightCyan, Color::Cyan),
+        h if h < 260.0 => pick(light, Color::LightBlue, Color::Blue),
+        _ => pick(light, Color::LightMagenta, Color::Magenta),
+    }
+}
+
+fn pick(light: bool, light_color: Color, color: Color) -> Color {
+    if light { light_color } else { color }
+}
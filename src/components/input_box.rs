@@ -5,11 +5,13 @@ use tui::{
     backend::Backend,
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::{Span, Spans},
-    widgets::{Block, Borders, Paragraph},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
 };
 
-use crate::state::AppState;
+use crate::{emoji, state::AppState, text};
+
+const COMPLETION_ROWS: usize = 8;
 
 pub struct InputBox {
     is_focused: bool,
@@ -36,7 +38,7 @@ impl InputBox {
 
         let input_text = if state.input_buffer.is_empty() {
             Spans::from(vec![Span::styled(
-                "Type your message here...",
+                "Type your message here, or /help for commands...",
                 Style::default()
                     .fg(Color::DarkGray)
                     .add_modifier(Modifier::ITALIC),
@@ -47,11 +49,12 @@ impl InputBox {
             let after_cursor =
                 &state.input_buffer[state.cursor_position.min(state.input_buffer.len())..];
 
-            let cursor_char = if state.cursor_position >= state.input_buffer.len() {
-                " "
-            } else {
-                &state.input_buffer[state.cursor_position..state.cursor_position + 1]
+            let cursor_end = text::next_boundary(&state.input_buffer, before_cursor.len());
+            let cursor_char = match &state.input_buffer[before_cursor.len()..cursor_end] {
+                "" => " ",
+                c => c,
             };
+            let after_cursor = &after_cursor[cursor_char.len().min(after_cursor.len())..];
 
             Spans::from(vec![
                 Span::styled(before_cursor, Style::default().fg(Color::White)),
@@ -66,8 +69,10 @@ impl InputBox {
             ])
         };
 
-        let char_count = format!(" {}/{} ", state.input_buffer.len(), 500);
-        let title = if self.is_focused {
+        let char_count = format!(" {}/{} ", state.input_buffer.chars().count(), 500);
+        let title = if state.editing.is_some() {
+            format!("Editing message (Esc to cancel){}", char_count)
+        } else if self.is_focused {
             format!("Input (Active){}", char_count)
         } else {
             format!("Input{}", char_count)
@@ -81,5 +86,76 @@ impl InputBox {
         let paragraph = Paragraph::new(input_text).block(block);
 
         f.render_widget(paragraph, area);
+
+        self.render_completion(f, area, &state);
+    }
+
+    fn render_completion<B: Backend>(&self, f: &mut Frame<B>, area: Rect, state: &AppState) {
+        let Some(completion) = &state.completion else {
+            return;
+        };
+
+        // The popup sits one column in from the input's border and must not run past it.
+        let max_width = area.width.saturating_sub(2);
+        if max_width < 4 {
+            return;
+        }
+
+        // Keep the selection in view by scrolling the list a page at a time.
+        let first = completion.selected / COMPLETION_ROWS * COMPLETION_ROWS;
+        let visible = completion
+            .candidates
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(COMPLETION_ROWS);
+        let height = visible.len() as u16 + 2;
+        let width = completion
+            .candidates
+            .iter()
+            .map(|c| c.chars().count() as u16 + 3)
+            .max()
+            .unwrap_or(0)
+            .saturating_add(4)
+            .max(16)
+            .min(max_width);
+        let popup_area = Rect {
+            x: area.x + 1,
+            y: area.y.saturating_sub(height),
+            width,
+            height: height.min(area.y),
+        };
+
+        let lines: Vec<Spans> = visible
+            .map(|(index, candidate)| {
+                let style = if index == completion.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                match candidate
+                    .strip_prefix(':')
+                    .and_then(|name| emoji::lookup(name.strip_suffix(':')?))
+                {
+                    Some(emoji) => Spans::from(vec![
+                        Span::raw(format!("{emoji} ")),
+                        Span::styled(candidate.clone(), style),
+                    ]),
+                    None => Spans::from(Span::styled(candidate.clone(), style)),
+                }
+            })
+            .collect();
+
+        let block = Block::default()
+            .title("Tab")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(Paragraph::new(Text::from(lines)).block(block), popup_area);
     }
 }
@@ -0,0 +1,124 @@
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+pub struct LinkPicker {
+    links: Vec<String>,
+    selected: usize,
+    is_visible: bool,
+}
+
+impl LinkPicker {
+    pub fn new() -> Self {
+        Self {
+            links: Vec::new(),
+            selected: 0,
+            is_visible: false,
+        }
+    }
+
+    pub fn open(&mut self, links: Vec<String>) {
+        self.links = links;
+        self.selected = 0;
+        self.is_visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.is_visible = false;
+    }
+
+    pub fn next(&mut self) {
+        if !self.links.is_empty() {
+            self.selected = (self.selected + 1) % self.links.len();
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.links.is_empty() {
+            self.selected = (self.selected + self.links.len() - 1) % self.links.len();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.links.get(self.selected).map(String::as_str)
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.is_visible {
+            return;
+        }
+
+        let size = f.size();
+
+        let popup_width = size.width.saturating_sub(10).min(90);
+        let popup_height = (self.links.len() as u16 + 4).min(size.height);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let mut lines: Vec<Spans> = self
+            .links
+            .iter()
+            .enumerate()
+            .map(|(index, link)| {
+                let style = if index == self.selected {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                        .fg(Color::LightBlue)
+                        .add_modifier(Modifier::UNDERLINED)
+                };
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{:>2}. ", index + 1),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(link.clone(), style),
+                ])
+            })
+            .collect();
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(vec![
+            Span::styled(
+                "Enter",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Open  "),
+            Span::styled(
+                "Esc",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Close"),
+        ]));
+
+        let block = Block::default()
+            .title(" Links ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(Paragraph::new(Text::from(lines)).block(block), popup_area);
+    }
+}
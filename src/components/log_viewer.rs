@@ -0,0 +1,117 @@
+use std::cell::Cell;
+
+use tracing::Level;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::tracing_writer::BufferedWriter;
+
+pub struct LogViewer {
+    writer: BufferedWriter,
+    max_level: Level,
+    scroll: usize,
+    // Rows shown at the last render, so scrolling can stop at the oldest line.
+    visible: Cell<usize>,
+    pub is_visible: bool,
+}
+
+impl LogViewer {
+    pub fn new(writer: BufferedWriter) -> Self {
+        Self {
+            writer,
+            max_level: Level::INFO,
+            scroll: 0,
+            visible: Cell::new(0),
+            is_visible: false,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_visible = !self.is_visible;
+        self.scroll = 0;
+    }
+
+    pub fn set_level(&mut self, key: char) {
+        let level = match key {
+            'e' => Level::ERROR,
+            'w' => Level::WARN,
+            'i' => Level::INFO,
+            'd' => Level::DEBUG,
+            't' => Level::TRACE,
+            _ => return,
+        };
+        self.max_level = level;
+        self.scroll = 0;
+    }
+
+    pub fn scroll_up(&mut self) {
+        let max = self
+            .writer
+            .count(self.max_level)
+            .saturating_sub(self.visible.get());
+        self.scroll = (self.scroll + 1).min(max);
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn follow(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect, is_focused: bool) {
+        let visible = area.height.saturating_sub(2) as usize;
+        self.visible.set(visible);
+        let total = self.writer.count(self.max_level);
+        let scroll = self.scroll.min(total.saturating_sub(visible));
+        let lines = self.writer.lines(self.max_level, scroll, visible);
+
+        let text: Vec<Spans> = lines
+            .into_iter()
+            .map(|line| {
+                let color = match line.level {
+                    Level::ERROR => Color::Red,
+                    Level::WARN => Color::Yellow,
+                    Level::INFO => Color::Green,
+                    Level::DEBUG => Color::Blue,
+                    Level::TRACE => Color::DarkGray,
+                };
+                Spans::from(Span::styled(line.text, Style::default().fg(color)))
+            })
+            .collect();
+
+        let position = if scroll == 0 {
+            "following".to_string()
+        } else {
+            format!("{scroll} lines up")
+        };
+        let title = if is_focused {
+            format!(
+                "Logs [{}] {position} (↑/↓ scroll, G follow, e/w/i/d/t level)",
+                self.max_level
+            )
+        } else {
+            format!("Logs [{}] {position}", self.max_level)
+        };
+
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(if is_focused {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Blue)
+            });
+
+        f.render_widget(Paragraph::new(Text::from(text)).block(block), area);
+    }
+}
@@ -0,0 +1,422 @@
+use std::time::Duration;
+
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::credentials;
+
+use super::{
+    Config,
+    form::{Form, FormField},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoginField {
+    Username,
+    Password,
+    ServerUrl,
+}
+
+const FIELDS: &[FormField<LoginField>] = &[
+    FormField {
+        id: LoginField::Username,
+        label: "Username",
+        masked: false,
+    },
+    FormField {
+        id: LoginField::Password,
+        label: "Password",
+        masked: true,
+    },
+    FormField {
+        id: LoginField::ServerUrl,
+        label: "Server URL",
+        masked: false,
+    },
+];
+
+// The controls below the text fields, reached by tabbing past the last one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Control {
+    Remember,
+    Test,
+}
+
+enum TestStatus {
+    Running,
+    Passed(Duration),
+    Failed(String),
+}
+
+pub enum LoginAction {
+    LogIn {
+        username: String,
+        password: String,
+        server_url: String,
+    },
+    Test(String),
+}
+
+pub struct Login {
+    username: String,
+    password: String,
+    server_url: String,
+    remember: bool,
+    form: Form<LoginField>,
+    control: Option<Control>,
+    error: Option<String>,
+    test: Option<TestStatus>,
+    pending: bool,
+    // Whether to save the login once the server accepts it, decided when it's submitted.
+    save: Option<bool>,
+    is_visible: bool,
+}
+
+impl Login {
+    pub fn new() -> Self {
+        Self {
+            username: String::new(),
+            password: String::new(),
+            server_url: String::new(),
+            remember: false,
+            form: Form::new(FIELDS),
+            control: None,
+            error: None,
+            test: None,
+            pending: false,
+            save: None,
+            is_visible: false,
+        }
+    }
+
+    pub fn open(&mut self, config: &Config, error: Option<String>) {
+        self.username = config.username.clone();
+        self.password = config.password.clone();
+        self.server_url = config.server_url.clone();
+        self.remember = credentials::load(&config.server_url).is_some();
+        self.control = None;
+        self.test = None;
+        self.pending = false;
+        self.is_visible = true;
+
+        // After a rejected login the password is the likely culprit.
+        let field = if !self.username.is_empty() && error.is_some() {
+            LoginField::Password
+        } else {
+            LoginField::Username
+        };
+        self.error = error;
+        let value = self.value(field).to_string();
+        self.form.focus(field, &value);
+    }
+
+    pub fn close(&mut self) {
+        self.is_visible = false;
+        self.password.clear();
+        self.save = None;
+    }
+
+    pub fn next_field(&mut self) {
+        self.control = match self.control {
+            None if self.form.is_last() => Some(Control::Remember),
+            None => {
+                self.form.next_field();
+                let (form, value) = self.focused_value();
+                form.cursor_to_end(value);
+                None
+            }
+            Some(Control::Remember) => Some(Control::Test),
+            Some(Control::Test) => {
+                self.form.focus(LoginField::Username, &self.username);
+                None
+            }
+        };
+        if self.control.is_some() {
+            self.form.blur();
+        }
+    }
+
+    pub fn previous_field(&mut self) {
+        self.control = match self.control {
+            None if self.form.is_first() => Some(Control::Test),
+            None => {
+                self.form.previous_field();
+                let (form, value) = self.focused_value();
+                form.cursor_to_end(value);
+                None
+            }
+            Some(Control::Test) => Some(Control::Remember),
+            Some(Control::Remember) => {
+                self.form.focus(LoginField::ServerUrl, &self.server_url);
+                None
+            }
+        };
+        if self.control.is_some() {
+            self.form.blur();
+        }
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        match self.control {
+            Some(Control::Remember) if c == ' ' => self.remember = !self.remember,
+            Some(_) => {}
+            None => {
+                let (form, value) = self.focused_value();
+                form.insert_char(value, c);
+            }
+        }
+    }
+
+    pub fn delete_char(&mut self) {
+        if self.control.is_none() {
+            let (form, value) = self.focused_value();
+            form.delete_char(value);
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.control.is_none() {
+            let (form, value) = self.focused_value();
+            form.backspace(value);
+        }
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        if self.control.is_none() {
+            let (form, value) = self.focused_value();
+            form.move_cursor_left(value);
+        }
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        if self.control.is_none() {
+            let (form, value) = self.focused_value();
+            form.move_cursor_right(value);
+        }
+    }
+
+    fn focused_value(&mut self) -> (&mut Form<LoginField>, &mut String) {
+        let value = match self.form.focused() {
+            LoginField::Username => &mut self.username,
+            LoginField::Password => &mut self.password,
+            LoginField::ServerUrl => &mut self.server_url,
+        };
+        (&mut self.form, value)
+    }
+
+    fn value(&self, field: LoginField) -> &str {
+        match field {
+            LoginField::Username => &self.username,
+            LoginField::Password => &self.password,
+            LoginField::ServerUrl => &self.server_url,
+        }
+    }
+
+    fn normalized_server_url(&self) -> String {
+        self.server_url.trim().trim_end_matches('/').to_string()
+    }
+
+    pub fn submit(&mut self) -> Option<LoginAction> {
+        match self.control {
+            Some(Control::Remember) => {
+                self.remember = !self.remember;
+                return None;
+            }
+            Some(Control::Test) => {
+                if !self.check_server_url() {
+                    return None;
+                }
+                self.test = Some(TestStatus::Running);
+                return Some(LoginAction::Test(self.normalized_server_url()));
+            }
+            None => {}
+        }
+
+        if self.pending {
+            return None;
+        }
+
+        let username = self.username.trim().to_string();
+        let problem = if username.is_empty() {
+            Some((LoginField::Username, "Enter your username"))
+        } else if self.password.is_empty() {
+            Some((LoginField::Password, "Enter your password"))
+        } else {
+            None
+        };
+        if let Some((field, message)) = problem {
+            self.error = Some(message.to_string());
+            let value = self.value(field).to_string();
+            self.form.focus(field, &value);
+            return None;
+        }
+        if !self.check_server_url() {
+            return None;
+        }
+
+        self.error = None;
+        self.pending = true;
+        self.save = Some(self.remember);
+        Some(LoginAction::LogIn {
+            username,
+            password: self.password.clone(),
+            server_url: self.normalized_server_url(),
+        })
+    }
+
+    fn check_server_url(&mut self) -> bool {
+        let server_url = self.normalized_server_url();
+        if server_url.starts_with("https://") || server_url.starts_with("http://") {
+            return true;
+        }
+
+        self.error = Some("The server URL must start with https:// or http://".to_string());
+        self.control = None;
+        self.form.focus(LoginField::ServerUrl, &self.server_url);
+        false
+    }
+
+    pub fn failed(&mut self, message: String) {
+        self.pending = false;
+        self.save = None;
+        self.error = Some(message);
+    }
+
+    // Returns whether to remember the login, if it was submitted from here.
+    pub fn succeeded(&mut self) -> Option<bool> {
+        self.pending = false;
+        self.save.take()
+    }
+
+    pub fn tested(&mut self, server_url: &str, result: Result<Duration, String>) {
+        // The URL may have been edited while the test was running.
+        if !self.is_visible || server_url != self.normalized_server_url() {
+            return;
+        }
+        self.test = Some(match result {
+            Ok(elapsed) => TestStatus::Passed(elapsed),
+            Err(message) => TestStatus::Failed(message),
+        });
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.is_visible {
+            return;
+        }
+
+        let size = f.size();
+
+        let popup_width = size.width.saturating_sub(10).min(70);
+        let popup_height = 21.min(size.height);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(" Log in ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(popup_block, popup_area);
+
+        let inner_area = Rect {
+            x: popup_area.x + 2,
+            y: popup_area.y + 2,
+            width: popup_area.width.saturating_sub(4),
+            height: popup_area.height.saturating_sub(4),
+        };
+
+        let rest_area = self.form.render(
+            f,
+            inner_area,
+            &[&self.username, &self.password, &self.server_url],
+        );
+
+        let control_style = |control| {
+            if self.control == Some(control) {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+
+        let checkbox = if self.remember { "[x]" } else { "[ ]" };
+        let remember_line = Spans::from(Span::styled(
+            format!("{checkbox} Remember me on this computer"),
+            control_style(Control::Remember),
+        ));
+
+        let test_result = match &self.test {
+            None => Span::raw(""),
+            Some(TestStatus::Running) => {
+                Span::styled("  Testing...", Style::default().fg(Color::Yellow))
+            }
+            Some(TestStatus::Passed(elapsed)) => Span::styled(
+                format!("  Server reachable ({} ms)", elapsed.as_millis()),
+                Style::default().fg(Color::Green),
+            ),
+            Some(TestStatus::Failed(message)) => {
+                Span::styled(format!("  {message}"), Style::default().fg(Color::Red))
+            }
+        };
+        let test_line = Spans::from(vec![
+            Span::styled("[ Test connection ]", control_style(Control::Test)),
+            test_result,
+        ]);
+
+        let status = match (&self.error, self.pending) {
+            (_, true) => Span::styled("Logging in...", Style::default().fg(Color::Yellow)),
+            (Some(error), false) => Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            (None, false) => Span::raw(""),
+        };
+
+        let key_style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let help_text = vec![
+            remember_line,
+            test_line,
+            Spans::from(""),
+            Spans::from(status),
+            Spans::from(vec![
+                Span::styled("Enter", key_style),
+                Span::raw(": Log in / select  "),
+                Span::styled("Tab/Shift+Tab", key_style),
+                Span::raw(": Move  "),
+                Span::styled("Esc", key_style),
+                Span::raw(": Close"),
+            ]),
+            Spans::from(Span::styled(
+                "No account yet? Close this and type /register",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(help_text).wrap(Wrap { trim: true });
+
+        f.render_widget(paragraph, rest_area);
+    }
+}
@@ -0,0 +1,166 @@
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+};
+
+use super::{highlight::Highlighter, message::link_ranges};
+
+pub enum Block<'a> {
+    Text(&'a str),
+    Quote(&'a str),
+    Code {
+        lang: Option<&'a str>,
+        lines: Vec<&'a str>,
+    },
+}
+
+pub fn parse_blocks(content: &str) -> Vec<Block<'_>> {
+    let mut blocks = Vec::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        if let Some(fence) = line.trim_start().strip_prefix("```") {
+            let lang = Some(fence.trim()).filter(|lang| !lang.is_empty());
+            let lines = lines
+                .by_ref()
+                .take_while(|line| line.trim() != "```")
+                .collect();
+            blocks.push(Block::Code { lang, lines });
+        } else if let Some(quote) = line.strip_prefix('>') {
+            blocks.push(Block::Quote(quote.strip_prefix(' ').unwrap_or(quote)));
+        } else {
+            blocks.push(Block::Text(line));
+        }
+    }
+
+    blocks
+}
+
+pub fn render(content: &str, base: Style, highlighter: &Highlighter) -> Vec<Spans<'static>> {
+    parse_blocks(content)
+        .into_iter()
+        .flat_map(|block| render_block(block, base, highlighter))
+        .collect()
+}
+
+fn render_block(block: Block<'_>, base: Style, highlighter: &Highlighter) -> Vec<Spans<'static>> {
+    match block {
+        Block::Text(text) => vec![Spans::from(inline(text, base))],
+        Block::Quote(text) => {
+            let quote_style = base.fg(Color::Gray).add_modifier(Modifier::ITALIC);
+            let mut spans = vec![Span::styled("▎ ", Style::default().fg(Color::DarkGray))];
+            spans.extend(inline(text, quote_style));
+            vec![Spans::from(spans)]
+        }
+        Block::Code { lang, lines } => {
+            let highlighted = lang.and_then(|lang| highlighter.highlight(lang, &lines));
+            let lines = highlighted.unwrap_or_else(|| {
+                let code_style = Style::default().fg(Color::White);
+                lines
+                    .into_iter()
+                    .map(|line| vec![Span::styled(line.to_string(), code_style)])
+                    .collect()
+            });
+            code_box(lang, lines)
+        }
+    }
+}
+
+pub fn code_box(lang: Option<&str>, lines: Vec<Vec<Span<'static>>>) -> Vec<Spans<'static>> {
+    let border_style = Style::default().fg(Color::DarkGray);
+    let header = match lang {
+        Some(lang) => format!("┌─ {lang} ─"),
+        None => "┌──".to_string(),
+    };
+
+    let mut rendered = vec![Spans::from(Span::styled(header, border_style))];
+    for line in lines {
+        let mut spans = vec![Span::styled("│ ", border_style)];
+        spans.extend(line);
+        rendered.push(Spans::from(spans));
+    }
+    rendered.push(Spans::from(Span::styled("└──", border_style)));
+    rendered
+}
+
+pub fn inline(text: &str, base: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    inline_into(text, base, &mut spans);
+    spans
+}
+
+fn inline_into(text: &str, style: Style, spans: &mut Vec<Span<'static>>) {
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '\\'
+            && let Some(escaped) = rest[1..].chars().next()
+            && "\\`*~".contains(escaped)
+        {
+            plain.push(escaped);
+            rest = &rest[1 + escaped.len_utf8()..];
+            continue;
+        }
+
+        if rest.starts_with("http")
+            && let Some(link) = link_ranges(rest).into_iter().find(|link| link.start == 0)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), style));
+            }
+            spans.push(Span::styled(
+                rest[link.clone()].to_string(),
+                style
+                    .fg(Color::LightBlue)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
+            rest = &rest[link.end..];
+            continue;
+        }
+
+        if let Some((delimiter, inner, after)) = delimited(rest) {
+            if !plain.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut plain), style));
+            }
+            match delimiter {
+                "`" => spans.push(Span::styled(
+                    inner.to_string(),
+                    Style::default()
+                        .fg(Color::LightYellow)
+                        .bg(Color::Rgb(40, 40, 40)),
+                )),
+                "**" => inline_into(inner, style.add_modifier(Modifier::BOLD), spans),
+                "~~" => inline_into(inner, style.add_modifier(Modifier::CROSSED_OUT), spans),
+                _ => inline_into(inner, style.add_modifier(Modifier::ITALIC), spans),
+            }
+            rest = after;
+            continue;
+        }
+
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, style));
+    }
+}
+
+fn delimited(text: &str) -> Option<(&'static str, &str, &str)> {
+    let delimiter = ["`", "**", "~~", "*"]
+        .into_iter()
+        .find(|delimiter| text.starts_with(delimiter))?;
+    let body = &text[delimiter.len()..];
+    if body.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let end = body.find(delimiter).filter(|end| *end > 0)?;
+    let inner = &body[..end];
+    if delimiter != "`" && inner.ends_with(char::is_whitespace) {
+        return None;
+    }
+
+    Some((delimiter, inner, &body[end + delimiter.len()..]))
+}
@@ -0,0 +1,142 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    network::User,
+    state::{AppState, FocusedItem},
+};
+
+use super::ConnectionStatus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Presence {
+    Online,
+    Idle,
+    #[serde(other)]
+    Offline,
+}
+
+pub struct MemberList {
+    app_state: Rc<RefCell<AppState>>,
+}
+
+impl MemberList {
+    pub fn new(app_state: Rc<RefCell<AppState>>) -> Self {
+        Self { app_state }
+    }
+
+    fn presence(state: &AppState, user: &User) -> Presence {
+        if let Some(presence) = user.presence {
+            return presence;
+        }
+
+        let is_self = state
+            .session_token
+            .as_ref()
+            .is_some_and(|token| token.user_id == user.id);
+        if is_self && state.connection_status == ConnectionStatus::Connected {
+            return Presence::Online;
+        }
+
+        match state.last_activity(user.id) {
+            Some(last) if Utc::now() - last < Duration::minutes(5) => Presence::Online,
+            Some(last) if Utc::now() - last < Duration::minutes(30) => Presence::Idle,
+            _ => Presence::Offline,
+        }
+    }
+
+    pub fn sorted_members(state: &AppState) -> Vec<(&User, Presence)> {
+        let mut members: Vec<(&User, Presence)> = state
+            .users
+            .iter()
+            .map(|user| (user, Self::presence(state, user)))
+            .collect();
+        members.sort_by(|(a, a_presence), (b, b_presence)| {
+            a_presence
+                .cmp(b_presence)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+        members
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let state = self.app_state.borrow();
+        let current_user = state.session_token.as_ref().map(|token| token.user_id);
+        let is_focused = matches!(state.focused_item, FocusedItem::Members);
+        let members = Self::sorted_members(&state);
+
+        let online = members
+            .iter()
+            .filter(|(_, presence)| *presence != Presence::Offline)
+            .count();
+
+        let text_lines: Vec<Spans> = members
+            .iter()
+            .enumerate()
+            .map(|(index, (user, presence))| {
+                let (indicator, indicator_color) = match presence {
+                    Presence::Online => ("● ", Color::Green),
+                    Presence::Idle => ("◐ ", Color::Yellow),
+                    Presence::Offline => ("○ ", Color::DarkGray),
+                };
+
+                let name_style = if is_focused && index == state.selected_member {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else if current_user == Some(user.id) {
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD)
+                } else if *presence == Presence::Offline {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+
+                let mut spans = vec![
+                    Span::styled(indicator, Style::default().fg(indicator_color)),
+                    Span::styled(user.name.clone(), name_style),
+                ];
+                if current_user == Some(user.id) {
+                    spans.push(Span::styled(
+                        " (you)",
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::ITALIC),
+                    ));
+                }
+                Spans::from(spans)
+            })
+            .collect();
+
+        let border_style = if is_focused {
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Blue)
+        };
+
+        let block = Block::default()
+            .title(format!("Members ({}/{})", online, members.len()))
+            .borders(Borders::ALL)
+            .border_style(border_style);
+
+        let paragraph = Paragraph::new(Text::from(text_lines)).block(block);
+
+        f.render_widget(paragraph, area);
+    }
+}
@@ -1,13 +1,55 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, ops::Range, sync::Arc};
 
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+pub const DEFAULT_CHANNEL: &str = "general";
 
 #[derive(Debug, Clone)]
 pub struct Message {
+    pub id: Option<u32>,
+    pub channel: Channel,
     pub timestamp: DateTime<Utc>,
     pub sender: MessageSender,
     pub content: String,
     pub username: Option<Arc<str>>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub reactions: Vec<Reaction>,
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reaction {
+    pub emoji: String,
+    pub user_ids: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Attachment {
+    pub id: u32,
+    pub filename: String,
+    pub size: u64,
+}
+
+impl Attachment {
+    pub fn size_label(&self) -> String {
+        format_size(self.size)
+    }
+}
+
+pub fn format_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if size < 1024 {
+        return format!("{size} B");
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -16,11 +58,58 @@ pub enum MessageSender {
     System,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    Public(String),
+    Direct(u32),
+}
+
+impl Channel {
+    pub fn is_direct(&self) -> bool {
+        matches!(self, Channel::Direct(_))
+    }
+}
+
+impl Default for Channel {
+    fn default() -> Self {
+        Channel::Public(DEFAULT_CHANNEL.to_string())
+    }
+}
+
 impl Message {
     pub fn format_time(&self) -> String {
         self.timestamp.format("%H:%M:%S").to_string()
     }
 
+    pub fn links(&self) -> Vec<&str> {
+        link_ranges(&self.content)
+            .into_iter()
+            .map(|range| &self.content[range])
+            .collect()
+    }
+
+    pub fn reacted(&self, emoji: &str, user_id: u32) -> bool {
+        self.reactions
+            .iter()
+            .any(|r| r.emoji == emoji && r.user_ids.contains(&user_id))
+    }
+
+    pub fn action(&self) -> Option<&str> {
+        self.content.strip_prefix("/me ")
+    }
+
+    pub fn mentions(&self, username: &str) -> bool {
+        let mention = format!("@{}", username.to_lowercase());
+        let content = self.content.to_lowercase();
+
+        content.match_indices(&mention).any(|(index, _)| {
+            let before = content[..index].chars().next_back();
+            let after = content[index + mention.len()..].chars().next();
+            let is_boundary = |c: Option<char>| !c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            is_boundary(before) && is_boundary(after)
+        })
+    }
+
     pub fn sender_name(&self) -> Cow<'_, str> {
         match self.sender {
             MessageSender::User(id) => self
@@ -32,3 +121,42 @@ impl Message {
         }
     }
 }
+
+pub fn link_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = ["https://", "http://"]
+        .iter()
+        .filter_map(|scheme| text[offset..].find(scheme))
+        .min()
+        .map(|start| offset + start)
+    {
+        let len = text[start..]
+            .find(|c: char| c.is_whitespace() || "<>\"`".contains(c))
+            .unwrap_or(text.len() - start);
+        let mut url = &text[start..start + len];
+
+        loop {
+            let trimmed = url.trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '*', '~']);
+            let trimmed = if trimmed.ends_with(')')
+                && trimmed.matches('(').count() < trimmed.matches(')').count()
+            {
+                &trimmed[..trimmed.len() - 1]
+            } else {
+                trimmed
+            };
+            if trimmed.len() == url.len() {
+                break;
+            }
+            url = trimmed;
+        }
+
+        if !url.ends_with("://") {
+            ranges.push(start..start + url.len());
+        }
+        offset = start + len.max(1);
+    }
+
+    ranges
+}
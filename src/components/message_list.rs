@@ -9,29 +9,65 @@ use tui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::state::AppState;
+use crate::state::{AppState, FocusedItem};
 
-use super::message::MessageSender;
+use super::{highlight::Highlighter, markdown, message::MessageSender};
 
 pub struct MessageList {
     app_state: Rc<RefCell<AppState>>,
+    highlighter: Highlighter,
 }
 
 impl MessageList {
     pub fn new(app_state: Rc<RefCell<AppState>>) -> Self {
-        Self { app_state }
+        Self {
+            app_state,
+            highlighter: Highlighter::new(),
+        }
     }
 
     pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
         let mut text_lines = Vec::new();
 
         let state = self.app_state.borrow();
-        for message in &state.messages {
+        let is_focused = state.focused_item == FocusedItem::Messages;
+        let mut selected_lines = 0..0;
+        let own_id = state.session_token.as_ref().map(|token| token.user_id);
+
+        let mut divider_drawn = false;
+        for (index, message) in state.channel_messages().enumerate() {
+            if let Some(divider) = state.unread_divider
+                && !divider_drawn
+                && message.id.is_some_and(|id| id <= divider)
+            {
+                divider_drawn = true;
+                if index > 0 {
+                    text_lines.push(Spans::from(Span::styled(
+                        format!(
+                            "{:─^1$}",
+                            " new messages ",
+                            area.width.saturating_sub(2) as usize
+                        ),
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    )));
+                }
+            }
+
+            let first_line = text_lines.len();
             let timestamp_style = Style::default()
                 .fg(Color::DarkGray)
                 .add_modifier(Modifier::DIM);
 
             let (sender_style, content_style) = match message.sender {
+                MessageSender::User(_) if state.mentions_me(message) => (
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                    Style::default()
+                        .fg(Color::White)
+                        .bg(Color::Rgb(48, 32, 64))
+                        .add_modifier(Modifier::BOLD),
+                ),
                 MessageSender::User(_) => (
                     Style::default()
                         .fg(Color::Cyan)
@@ -48,26 +84,124 @@ impl MessageList {
                 ),
             };
 
-            let line = Spans::from(vec![
-                Span::styled(format!("[{}] ", message.format_time()), timestamp_style),
-                Span::styled(format!("{}: ", message.sender_name()), sender_style),
-                Span::styled(message.content.clone(), content_style),
-            ]);
+            let timestamp = Span::styled(format!("[{}] ", message.format_time()), timestamp_style);
+
+            if let Some(action) = message.action() {
+                let action_style = sender_style.add_modifier(Modifier::ITALIC);
+                let mut spans = vec![
+                    timestamp,
+                    Span::styled(format!("* {} ", message.sender_name()), action_style),
+                ];
+                spans.extend(markdown::inline(action, action_style));
+                text_lines.push(Spans::from(spans));
+            } else {
+                let prefix = vec![
+                    timestamp,
+                    Span::styled(format!("{}: ", message.sender_name()), sender_style),
+                ];
+
+                let mut lines =
+                    markdown::render(&message.content, content_style, &self.highlighter)
+                        .into_iter();
+                if message.content.trim_start().starts_with("```") {
+                    text_lines.push(Spans::from(prefix));
+                } else {
+                    let mut spans = prefix;
+                    spans.extend(lines.next().map(|line| line.0).unwrap_or_default());
+                    text_lines.push(Spans::from(spans));
+                }
+                for line in lines {
+                    let mut spans = vec![Span::raw("  ")];
+                    spans.extend(line.0);
+                    text_lines.push(Spans::from(spans));
+                }
+            }
+
+            if message.edited_at.is_some()
+                && let Some(last) = text_lines.last_mut()
+            {
+                last.0.push(Span::styled(" (edited)", timestamp_style));
+            }
 
-            text_lines.push(line);
+            for attachment in &message.attachments {
+                text_lines.push(Spans::from(vec![
+                    Span::raw("  📎 "),
+                    Span::styled(
+                        attachment.filename.clone(),
+                        Style::default()
+                            .fg(Color::LightBlue)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    Span::styled(format!(" ({})", attachment.size_label()), timestamp_style),
+                ]));
+            }
+
+            if !message.reactions.is_empty() {
+                let mut spans = vec![Span::raw("  ")];
+                for reaction in &message.reactions {
+                    let style = if own_id.is_some_and(|id| reaction.user_ids.contains(&id)) {
+                        Style::default()
+                            .fg(Color::White)
+                            .bg(Color::Rgb(30, 60, 90))
+                            .add_modifier(Modifier::BOLD)
+                    } else {
+                        Style::default().fg(Color::Gray).bg(Color::Rgb(40, 40, 40))
+                    };
+                    spans.push(Span::styled(
+                        format!(" {} {} ", reaction.emoji, reaction.user_ids.len()),
+                        style,
+                    ));
+                    spans.push(Span::raw(" "));
+                }
+                text_lines.push(Spans::from(spans));
+            }
+
+            if is_focused && index == state.selected_message {
+                selected_lines = first_line..text_lines.len();
+                for line in &mut text_lines[selected_lines.clone()] {
+                    for span in &mut line.0 {
+                        span.style = span.style.bg(Color::DarkGray);
+                    }
+                }
+            }
         }
 
         let text = Text::from(text_lines);
 
         let block = Block::default()
-            .title("Messages")
+            .title(format!(
+                "Messages - {}{}",
+                state.channel_name(&state.active_channel),
+                match (is_focused, state.pending_delete) {
+                    (true, Some(_)) => " (press d again to delete, Esc to cancel)",
+                    (true, None) => " (o: links, s: save, r: react, e: edit, d: delete)",
+                    (false, _) => "",
+                }
+            ))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Blue));
+            .border_style(if is_focused {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Blue)
+            });
+
+        let visible_lines = area.height.saturating_sub(2);
+        let scroll_offset = if is_focused {
+            let (start, end) = (selected_lines.start as u16, selected_lines.end as u16);
+            state
+                .scroll_offset
+                .min(start)
+                .max(end.saturating_sub(visible_lines))
+        } else {
+            state.scroll_offset
+        };
 
         let paragraph = Paragraph::new(text)
             .block(block)
             .wrap(Wrap { trim: false })
-            .scroll((state.scroll_offset, 0));
+            .scroll((scroll_offset, 0));
 
         f.render_widget(paragraph, area);
     }
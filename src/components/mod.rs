@@ -1,11 +1,32 @@
+pub mod account_picker;
+pub mod channel_list;
 pub mod config;
+pub mod emoji_picker;
+pub mod form;
+pub mod highlight;
 pub mod input_box;
+pub mod link_picker;
+pub mod log_viewer;
+pub mod login;
+pub mod markdown;
+pub mod member_list;
 pub mod message;
 pub mod message_list;
+pub mod register;
 pub mod status_bar;
+pub mod toast;
 
+pub use account_picker::AccountPicker;
+pub use channel_list::ChannelList;
 pub use config::Config;
+pub use emoji_picker::{EmojiPicker, EmojiTarget};
 pub use input_box::InputBox;
-pub use message::{Message, MessageSender};
+pub use link_picker::LinkPicker;
+pub use log_viewer::LogViewer;
+pub use login::{Login, LoginAction};
+pub use member_list::{MemberList, Presence};
+pub use message::{Attachment, Channel, Message, MessageSender, Reaction};
 pub use message_list::MessageList;
+pub use register::Register;
 pub use status_bar::{ConnectionStatus, StatusBar};
+pub use toast::{Severity, Toast, Toasts};
@@ -0,0 +1,269 @@
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::network::{AuthRequest, NetworkError};
+
+use super::form::{Form, FormField};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RegisterField {
+    Username,
+    Password,
+    Confirm,
+}
+
+const FIELDS: &[FormField<RegisterField>] = &[
+    FormField {
+        id: RegisterField::Username,
+        label: "Username",
+        masked: false,
+    },
+    FormField {
+        id: RegisterField::Password,
+        label: "Password",
+        masked: true,
+    },
+    FormField {
+        id: RegisterField::Confirm,
+        label: "Confirm password",
+        masked: true,
+    },
+];
+
+pub struct Register {
+    username: String,
+    password: String,
+    confirm: String,
+    server_url: String,
+    form: Form<RegisterField>,
+    error: Option<String>,
+    pending: bool,
+    is_visible: bool,
+}
+
+impl Register {
+    pub fn new() -> Self {
+        Self {
+            username: String::new(),
+            password: String::new(),
+            confirm: String::new(),
+            server_url: String::new(),
+            form: Form::new(FIELDS),
+            error: None,
+            pending: false,
+            is_visible: false,
+        }
+    }
+
+    pub fn open(&mut self, server_url: &str) {
+        self.server_url = server_url.to_string();
+        self.error = None;
+        self.pending = false;
+        self.is_visible = true;
+        let (form, value) = self.focused_value();
+        form.cursor_to_end(value);
+    }
+
+    pub fn close(&mut self) {
+        self.is_visible = false;
+        self.password.clear();
+        self.confirm.clear();
+    }
+
+    pub fn next_field(&mut self) {
+        self.form.next_field();
+        let (form, value) = self.focused_value();
+        form.cursor_to_end(value);
+    }
+
+    pub fn previous_field(&mut self) {
+        self.form.previous_field();
+        let (form, value) = self.focused_value();
+        form.cursor_to_end(value);
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let (form, value) = self.focused_value();
+        form.insert_char(value, c);
+    }
+
+    pub fn delete_char(&mut self) {
+        let (form, value) = self.focused_value();
+        form.delete_char(value);
+    }
+
+    pub fn backspace(&mut self) {
+        let (form, value) = self.focused_value();
+        form.backspace(value);
+    }
+
+    pub fn move_cursor_left(&mut self) {
+        let (form, value) = self.focused_value();
+        form.move_cursor_left(value);
+    }
+
+    pub fn move_cursor_right(&mut self) {
+        let (form, value) = self.focused_value();
+        form.move_cursor_right(value);
+    }
+
+    fn focused_value(&mut self) -> (&mut Form<RegisterField>, &mut String) {
+        let value = match self.form.focused() {
+            RegisterField::Username => &mut self.username,
+            RegisterField::Password => &mut self.password,
+            RegisterField::Confirm => &mut self.confirm,
+        };
+        (&mut self.form, value)
+    }
+
+    // Checks the input locally and returns the request to send, or shows what's wrong.
+    pub fn submit(&mut self) -> Option<AuthRequest> {
+        if self.pending {
+            return None;
+        }
+
+        let username = self.username.trim();
+        let problem = if username.is_empty() {
+            Some((RegisterField::Username, "Choose a username".to_string()))
+        } else if username.contains(char::is_whitespace) {
+            Some((
+                RegisterField::Username,
+                "Usernames can't contain spaces".to_string(),
+            ))
+        } else if self.password.chars().count() < MIN_PASSWORD_LENGTH {
+            Some((
+                RegisterField::Password,
+                format!("Passwords need at least {MIN_PASSWORD_LENGTH} characters"),
+            ))
+        } else if self.password != self.confirm {
+            Some((RegisterField::Confirm, "Passwords don't match".to_string()))
+        } else {
+            None
+        };
+
+        if let Some((field, message)) = problem {
+            self.error = Some(message);
+            let value = self.value(field).to_string();
+            self.form.focus(field, &value);
+            return None;
+        }
+
+        self.error = None;
+        self.pending = true;
+        Some(AuthRequest {
+            name: username.to_string(),
+            password: self.password.clone(),
+        })
+    }
+
+    pub fn failed(&mut self, error: &NetworkError) {
+        self.pending = false;
+        self.error = Some(error.user_message());
+        if error.is_username_taken() {
+            self.form.focus(RegisterField::Username, &self.username);
+        }
+    }
+
+    fn value(&self, field: RegisterField) -> &str {
+        match field {
+            RegisterField::Username => &self.username,
+            RegisterField::Password => &self.password,
+            RegisterField::Confirm => &self.confirm,
+        }
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if !self.is_visible {
+            return;
+        }
+
+        let size = f.size();
+
+        let popup_width = size.width.saturating_sub(10).min(70);
+        let popup_height = 17.min(size.height);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .title(format!(" Register on {} ", self.server_url))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(popup_block, popup_area);
+
+        let inner_area = Rect {
+            x: popup_area.x + 2,
+            y: popup_area.y + 2,
+            width: popup_area.width.saturating_sub(4),
+            height: popup_area.height.saturating_sub(4),
+        };
+
+        let help_area = self.form.render(
+            f,
+            inner_area,
+            &[&self.username, &self.password, &self.confirm],
+        );
+
+        let status = match (&self.error, self.pending) {
+            (_, true) => Span::styled("Creating account...", Style::default().fg(Color::Yellow)),
+            (Some(error), false) => Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            (None, false) => Span::raw(""),
+        };
+
+        let help_text = vec![
+            Spans::from(status),
+            Spans::from(vec![
+                Span::styled(
+                    "Enter",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(": Register  "),
+                Span::styled(
+                    "Tab/Shift+Tab",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(": Switch fields  "),
+                Span::styled(
+                    "Esc",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(": Cancel"),
+            ]),
+        ];
+
+        let help_paragraph = Paragraph::new(help_text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(help_paragraph, help_area);
+    }
+}
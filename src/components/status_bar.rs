@@ -1,5 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
+use tokio::time::Instant;
 use tui::{
     Frame,
     backend::Backend,
@@ -9,13 +10,21 @@ use tui::{
     widgets::Paragraph,
 };
 
-use crate::state::AppState;
+use crate::{
+    http::{self, Transport},
+    state::AppState,
+};
+
+use super::message::format_size;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ConnectionStatus {
-    Connected,
-    Disconnected,
+    Idle,
+    NeedsCredentials,
     Connecting,
+    Connected,
+    Backoff { until: Instant },
+    Failed,
 }
 
 pub struct StatusBar {
@@ -30,24 +39,104 @@ impl StatusBar {
     pub fn render<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
         let state = self.app_state.borrow();
         let (status_text, status_color) = match &state.connection_status {
-            ConnectionStatus::Connected => ("Connected", Color::Green),
-            ConnectionStatus::Disconnected => ("Disconnected", Color::Red),
-            ConnectionStatus::Connecting => ("Connecting...", Color::Yellow),
+            ConnectionStatus::Idle => ("Disconnected".to_string(), Color::Red),
+            ConnectionStatus::NeedsCredentials => {
+                ("Not logged in (/login)".to_string(), Color::Yellow)
+            }
+            ConnectionStatus::Connecting => ("Connecting...".to_string(), Color::Yellow),
+            ConnectionStatus::Connected => ("Connected".to_string(), Color::Green),
+            ConnectionStatus::Backoff { until } => {
+                let remaining = until.saturating_duration_since(Instant::now());
+                (
+                    format!("Retrying in {}s (Ctrl+R)", remaining.as_secs() + 1),
+                    Color::Yellow,
+                )
+            }
+            ConnectionStatus::Failed => ("Failed (Ctrl+R to retry)".to_string(), Color::Red),
+        };
+
+        let (lock, lock_color) = match http::transport(&state.server_url) {
+            Transport::Secure => ("🔒", Color::Green),
+            Transport::Local => ("🔓", Color::Yellow),
+            Transport::Plaintext => ("🔓 unencrypted", Color::Red),
         };
 
-        let spans = Spans::from(vec![
+        let mut spans = vec![
+            Span::styled(
+                format!(" {lock}"),
+                Style::default().fg(lock_color).add_modifier(Modifier::BOLD),
+            ),
             Span::styled(
                 format!(" {} ", status_text),
                 Style::default()
                     .fg(status_color)
                     .add_modifier(Modifier::BOLD),
             ),
+        ];
+        if let Some(account) = &state.account_name {
+            spans.insert(
+                0,
+                Span::styled(
+                    format!(" [{account}]"),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            );
+        }
+        if state.connection_status != ConnectionStatus::Connected
+            && let Some(error) = &state.last_error
+        {
+            spans.push(Span::styled(
+                format!("{} ", error.chars().take(60).collect::<String>()),
+                Style::default().fg(Color::Red).add_modifier(Modifier::DIM),
+            ));
+        }
+        spans.extend([
             Span::styled(" | ", Style::default().fg(Color::DarkGray)),
             Span::styled(
-                format!("{} messages", state.messages.len()),
+                format!("{} messages", state.channel_messages().count()),
                 Style::default().fg(Color::White),
             ),
+            Span::styled(
+                format!(", {} unread", state.total_unread()),
+                Style::default().fg(if state.total_unread() > 0 {
+                    Color::Yellow
+                } else {
+                    Color::White
+                }),
+            ),
             Span::styled(" | ", Style::default().fg(Color::DarkGray)),
+        ]);
+        let typing = state.typing_names();
+        if !typing.is_empty() {
+            let text = match typing.as_slice() {
+                [name] => format!("{name} is typing…"),
+                [first, second] => format!("{first} and {second} are typing…"),
+                _ => format!("{} people are typing…", typing.len()),
+            };
+            spans.push(Span::styled(
+                text,
+                Style::default()
+                    .fg(Color::Gray)
+                    .add_modifier(Modifier::ITALIC),
+            ));
+            spans.push(Span::styled(" | ", Style::default().fg(Color::DarkGray)));
+        }
+        if let Some(upload) = &state.upload {
+            let percent = (upload.sent * 100).checked_div(upload.total).unwrap_or(100);
+            spans.push(Span::styled(
+                format!(
+                    "Uploading {} {percent}% ({}/{})",
+                    upload.filename,
+                    format_size(upload.sent),
+                    format_size(upload.total)
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+            spans.push(Span::styled(" | ", Style::default().fg(Color::DarkGray)));
+        }
+        spans.extend([
             Span::styled(
                 "[↑/↓]",
                 Style::default()
@@ -69,6 +158,13 @@ impl StatusBar {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(" Clear ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Tab]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Focus ", Style::default().fg(Color::White)),
             Span::styled(
                 "[Ctrl+S]",
                 Style::default()
@@ -76,6 +172,48 @@ impl StatusBar {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(" Config ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+E]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Emoji ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+L]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Logs ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+T]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Notifications ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+A]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Accounts ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+U]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Members ", Style::default().fg(Color::White)),
+            Span::styled(
+                "[Ctrl+N/P]",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" Channel ", Style::default().fg(Color::White)),
             Span::styled(
                 "[Ctrl+C]",
                 Style::default()
@@ -85,7 +223,7 @@ impl StatusBar {
             Span::styled(" Quit ", Style::default().fg(Color::White)),
         ]);
 
-        let paragraph = Paragraph::new(spans).style(Style::default().fg(Color::White));
+        let paragraph = Paragraph::new(Spans::from(spans)).style(Style::default().fg(Color::White));
 
         f.render_widget(paragraph, area);
     }
@@ -0,0 +1,258 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use chrono::{DateTime, Local};
+use tokio::time::Instant;
+use tui::{
+    Frame,
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use crate::state::AppState;
+
+const MAX_VISIBLE: usize = 3;
+const TOAST_WIDTH: u16 = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Severity::Info => Color::Cyan,
+            Severity::Warning => Color::Yellow,
+            Severity::Error => Color::Red,
+        }
+    }
+
+    // Errors stay up a little longer so there's time to read them.
+    fn lifetime(self) -> Duration {
+        match self {
+            Severity::Info => Duration::from_secs(4),
+            Severity::Warning => Duration::from_secs(6),
+            Severity::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub severity: Severity,
+    pub message: String,
+    pub count: usize,
+    pub shown_at: Instant,
+    pub time: DateTime<Local>,
+    pub dismissed: bool,
+}
+
+impl Toast {
+    pub fn new(severity: Severity, message: String) -> Self {
+        Self {
+            severity,
+            message,
+            count: 1,
+            shown_at: Instant::now(),
+            time: Local::now(),
+            dismissed: false,
+        }
+    }
+
+    pub fn is_active(&self, now: Instant) -> bool {
+        !self.dismissed && now.duration_since(self.shown_at) < self.severity.lifetime()
+    }
+
+    fn text(&self) -> String {
+        if self.count > 1 {
+            format!("{} (x{})", self.message, self.count)
+        } else {
+            self.message.clone()
+        }
+    }
+}
+
+pub struct Toasts {
+    app_state: Rc<RefCell<AppState>>,
+    scroll: usize,
+    pub is_visible: bool,
+}
+
+impl Toasts {
+    pub fn new(app_state: Rc<RefCell<AppState>>) -> Self {
+        Self {
+            app_state,
+            scroll: 0,
+            is_visible: false,
+        }
+    }
+
+    pub fn open_history(&mut self) {
+        self.scroll = 0;
+        self.is_visible = true;
+    }
+
+    pub fn close_history(&mut self) {
+        self.is_visible = false;
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self) {
+        let len = self.app_state.borrow().toasts.len();
+        self.scroll = (self.scroll + 1).min(len.saturating_sub(1));
+    }
+
+    pub fn render<B: Backend>(&self, f: &mut Frame<B>) {
+        if self.is_visible {
+            self.render_history(f);
+        } else {
+            self.render_active(f);
+        }
+    }
+
+    fn render_active<B: Backend>(&self, f: &mut Frame<B>) {
+        let state = self.app_state.borrow();
+        let now = Instant::now();
+        let size = f.size();
+        let width = TOAST_WIDTH.min(size.width.saturating_sub(2));
+        if width < 10 {
+            return;
+        }
+
+        let mut y = 1;
+        for toast in state
+            .toasts
+            .iter()
+            .rev()
+            .filter(|toast| toast.is_active(now))
+            .take(MAX_VISIBLE)
+        {
+            let text = toast.text();
+            let inner_width = width.saturating_sub(2).max(1) as usize;
+            let lines = text.chars().count().div_ceil(inner_width).max(1) as u16;
+            let height = (lines + 2).min(5);
+            if y + height > size.height {
+                break;
+            }
+
+            let area = Rect {
+                x: size.width.saturating_sub(width + 1),
+                y,
+                width,
+                height,
+            };
+            y += height;
+
+            let color = toast.severity.color();
+            let block = Block::default()
+                .title(Span::styled(
+                    format!(" {} ", toast.severity.label()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(color))
+                .style(Style::default().bg(Color::Black));
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(text)
+                    .style(Style::default().fg(Color::White))
+                    .wrap(Wrap { trim: true })
+                    .block(block),
+                area,
+            );
+        }
+    }
+
+    fn render_history<B: Backend>(&self, f: &mut Frame<B>) {
+        let state = self.app_state.borrow();
+        let size = f.size();
+
+        let popup_width = size.width.saturating_sub(10).min(100);
+        let popup_height = size.height.saturating_sub(6).min(24);
+        let popup_area = Rect {
+            x: (size.width.saturating_sub(popup_width)) / 2,
+            y: (size.height.saturating_sub(popup_height)) / 2,
+            width: popup_width,
+            height: popup_height,
+        };
+
+        f.render_widget(Clear, popup_area);
+
+        let mut lines: Vec<Spans> = if state.toasts.is_empty() {
+            vec![Spans::from(Span::styled(
+                "No notifications yet",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            state
+                .toasts
+                .iter()
+                .rev()
+                .skip(self.scroll)
+                .map(|toast| {
+                    Spans::from(vec![
+                        Span::styled(
+                            format!("[{}] ", toast.time.format("%H:%M:%S")),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            format!("{:<8}", toast.severity.label()),
+                            Style::default()
+                                .fg(toast.severity.color())
+                                .add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(toast.text()),
+                    ])
+                })
+                .collect()
+        };
+        lines.truncate(popup_height.saturating_sub(4) as usize);
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(vec![
+            Span::styled(
+                "Up/Down",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Scroll  "),
+            Span::styled(
+                "Esc",
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(": Close"),
+        ]));
+
+        let block = Block::default()
+            .title(" Notifications ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .style(Style::default().bg(Color::Black));
+
+        f.render_widget(Paragraph::new(Text::from(lines)).block(block), popup_area);
+    }
+}
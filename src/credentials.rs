@@ -0,0 +1,85 @@
+use std::{collections::HashMap, fs, io::Write, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{http, paths};
+
+const FILE_NAME: &str = "credentials.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct CredentialsFile {
+    servers: HashMap<String, SavedLogin>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedLogin {
+    pub username: String,
+    pub password: String,
+}
+
+// Logins are keyed by origin, so a password is only ever sent to the scheme, host and port
+// it was saved for. The one exception is a login saved over http, which may be reused once
+// the same server is reached over https.
+pub fn load(server_url: &str) -> Option<SavedLogin> {
+    let mut servers = read().servers;
+    servers.remove(&http::origin(server_url)).or_else(|| {
+        let plaintext = server_url.strip_prefix("https://")?;
+        servers.remove(&http::origin(&format!("http://{plaintext}")))
+    })
+}
+
+pub fn remember(server_url: &str, login: SavedLogin) {
+    let mut file = read();
+    file.servers.insert(http::origin(server_url), login);
+    write(&file);
+}
+
+pub fn forget(server_url: &str) {
+    let mut file = read();
+    if file.servers.remove(&http::origin(server_url)).is_some() {
+        write(&file);
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(FILE_NAME))
+}
+
+fn read() -> CredentialsFile {
+    path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| match serde_json::from_str(&contents) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                tracing::warn!("ignoring unreadable saved logins: {e}");
+                None
+            }
+        })
+        .unwrap_or_default()
+}
+
+fn write(file: &CredentialsFile) {
+    let Some(path) = path() else {
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            let contents = serde_json::to_string_pretty(file)?;
+            let mut output = fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&path)?;
+            // The passwords are stored as-is, so keep the file to ourselves. The mode is set
+            // before writing because an existing file may have been created more open.
+            #[cfg(unix)]
+            output.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+            output.write_all(contents.as_bytes())
+        });
+    if let Err(e) = result {
+        tracing::warn!("failed to save logins to {}: {e}", path.display());
+    }
+}
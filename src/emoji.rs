@@ -0,0 +1,177 @@
+pub const EMOJI: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("angry", "😠"),
+    ("astonished", "😲"),
+    ("beer", "🍺"),
+    ("blush", "😊"),
+    ("bomb", "💣"),
+    ("boom", "💥"),
+    ("bug", "🐛"),
+    ("cake", "🍰"),
+    ("cat", "🐱"),
+    ("check", "✅"),
+    ("clap", "👏"),
+    ("coffee", "☕"),
+    ("cold_sweat", "😰"),
+    ("confused", "😕"),
+    ("cool", "🆒"),
+    ("cry", "😢"),
+    ("dog", "🐶"),
+    ("eyes", "👀"),
+    ("facepalm", "🤦"),
+    ("fire", "🔥"),
+    ("flushed", "😳"),
+    ("ghost", "👻"),
+    ("gift", "🎁"),
+    ("grimacing", "😬"),
+    ("grin", "😁"),
+    ("grinning", "😀"),
+    ("hankey", "💩"),
+    ("heart", "❤️"),
+    ("heart_eyes", "😍"),
+    ("hourglass", "⌛"),
+    ("hugs", "🤗"),
+    ("hushed", "😯"),
+    ("innocent", "😇"),
+    ("joy", "😂"),
+    ("kiss", "😘"),
+    ("laughing", "😆"),
+    ("memo", "📝"),
+    ("money_mouth_face", "🤑"),
+    ("muscle", "💪"),
+    ("nerd_face", "🤓"),
+    ("neutral_face", "😐"),
+    ("no_mouth", "😶"),
+    ("ok_hand", "👌"),
+    ("open_mouth", "😮"),
+    ("party", "🥳"),
+    ("pensive", "😔"),
+    ("pizza", "🍕"),
+    ("point_up", "👆"),
+    ("pray", "🙏"),
+    ("rage", "😡"),
+    ("raised_hands", "🙌"),
+    ("relieved", "😌"),
+    ("robot", "🤖"),
+    ("rocket", "🚀"),
+    ("rofl", "🤣"),
+    ("scream", "😱"),
+    ("see_no_evil", "🙈"),
+    ("shrug", "🤷"),
+    ("skull", "💀"),
+    ("sleeping", "😴"),
+    ("slightly_smiling_face", "🙂"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("smirk", "😏"),
+    ("sob", "😭"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("star_struck", "🤩"),
+    ("stuck_out_tongue", "😛"),
+    ("sunglasses", "😎"),
+    ("sweat_smile", "😅"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("tired_face", "😫"),
+    ("trophy", "🏆"),
+    ("unamused", "😒"),
+    ("upside_down_face", "🙃"),
+    ("wave", "👋"),
+    ("weary", "😩"),
+    ("wink", "😉"),
+    ("x", "❌"),
+    ("yawning_face", "🥱"),
+    ("yum", "😋"),
+    ("zany_face", "🤪"),
+    ("zap", "⚡"),
+    ("zipper_mouth_face", "🤐"),
+];
+
+pub fn lookup(shortcode: &str) -> Option<&'static str> {
+    EMOJI
+        .iter()
+        .find(|(name, _)| *name == shortcode)
+        .map(|(_, emoji)| *emoji)
+}
+
+pub fn expand(text: &str) -> String {
+    // Odd segments sit between backticks and are left as code. A trailing unmatched backtick
+    // is shown as-is, so what follows it is still text.
+    let parts: Vec<&str> = text.split('`').collect();
+    let unclosed = parts.len().is_multiple_of(2);
+    parts
+        .iter()
+        .enumerate()
+        .map(|(index, part)| {
+            if index % 2 == 1 && !(unclosed && index == parts.len() - 1) {
+                return part.to_string();
+            }
+
+            let mut expanded = String::new();
+            let mut rest = *part;
+            while let Some(start) = rest.find(':') {
+                expanded.push_str(&rest[..start]);
+                let after = &rest[start + 1..];
+                match after
+                    .find(':')
+                    .and_then(|end| Some((end, lookup(&after[..end])?)))
+                {
+                    Some((end, emoji)) => {
+                        expanded.push_str(emoji);
+                        rest = &after[end + 1..];
+                    }
+                    None => {
+                        expanded.push(':');
+                        rest = after;
+                    }
+                }
+            }
+            expanded.push_str(rest);
+            expanded
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+pub fn search(query: &str) -> Vec<(&'static str, &'static str)> {
+    let query = query.trim_matches(':').to_lowercase();
+    let mut matches: Vec<(&str, &str)> = EMOJI
+        .iter()
+        .filter(|(name, _)| name.contains(&query))
+        .copied()
+        .collect();
+    matches.sort_by_key(|(name, _)| !name.starts_with(&query));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_known_shortcodes() {
+        assert_eq!(expand("nice :+1::tada:"), "nice 👍🎉");
+        assert_eq!(expand(":nope: stays"), ":nope: stays");
+        assert_eq!(expand("at 10:30:00"), "at 10:30:00");
+        assert_eq!(expand("a: :fire:"), "a: 🔥");
+    }
+
+    #[test]
+    fn leaves_code_alone() {
+        assert_eq!(expand(":fire: `:fire:` :fire:"), "🔥 `:fire:` 🔥");
+        assert_eq!(expand("``:fire:"), "``🔥");
+        assert_eq!(expand("a ` b :fire:"), "a ` b 🔥");
+        assert_eq!(expand("`x` ` :fire:"), "`x` ` 🔥");
+    }
+
+    #[test]
+    fn search_ranks_prefix_matches_first() {
+        let names: Vec<&str> = search(":gri").iter().map(|(name, _)| *name).collect();
+        assert!(names.starts_with(&["grimacing", "grin", "grinning"]));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Local, NaiveDate};
+use tracing_subscriber::fmt::MakeWriter;
+
+const PREFIX: &str = "message-client-";
+const SUFFIX: &str = ".log";
+
+struct LogFile {
+    dir: PathBuf,
+    max_size: u64,
+    keep: usize,
+    date: NaiveDate,
+    index: u32,
+    file: File,
+    size: u64,
+    // Problems can't be printed while the UI owns the terminal, or logged from inside
+    // the log writer, so they wait here until shutdown.
+    errors: Vec<String>,
+}
+
+impl LogFile {
+    fn open(dir: &Path, date: NaiveDate, index: u32) -> io::Result<(File, u64)> {
+        let path = dir.join(format!("{PREFIX}{date}.{index}{SUFFIX}"));
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    fn rotate_if_needed(&mut self) -> io::Result<()> {
+        let today = Local::now().date_naive();
+        if today == self.date && self.size < self.max_size {
+            return Ok(());
+        }
+
+        if today != self.date {
+            self.date = today;
+            self.index = 0;
+        } else {
+            self.index += 1;
+        }
+        (self.file, self.size) = Self::open(&self.dir, self.date, self.index)?;
+        let errors = prune(&self.dir, self.keep);
+        self.errors.extend(errors);
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct RotatingWriter {
+    inner: Arc<Mutex<LogFile>>,
+}
+
+impl RotatingWriter {
+    pub fn new(dir: PathBuf, max_size: u64, keep: usize) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let date = Local::now().date_naive();
+        // Continue today's newest file so restarts don't leave a trail of tiny logs.
+        let index = latest_index(&dir, date).unwrap_or(0);
+        let (file, size) = LogFile::open(&dir, date, index)?;
+        let errors = prune(&dir, keep);
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(LogFile {
+                dir,
+                max_size,
+                keep,
+                date,
+                index,
+                file,
+                size,
+                errors,
+            })),
+        })
+    }
+
+    pub fn take_errors(&self) -> Vec<String> {
+        std::mem::take(&mut self.inner.lock().unwrap().errors)
+    }
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut log = self.inner.lock().unwrap();
+        log.rotate_if_needed()?;
+        let written = log.file.write(buf)?;
+        log.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.lock().unwrap().file.flush()
+    }
+}
+
+impl<'a> MakeWriter<'a> for RotatingWriter {
+    type Writer = Self;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+fn log_files(dir: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let stem = name.strip_prefix(PREFIX)?.strip_suffix(SUFFIX)?.to_string();
+            Some((entry.path(), stem))
+        })
+        .collect()
+}
+
+fn parse_stem(stem: &str) -> Option<(NaiveDate, u32)> {
+    let (date, index) = stem.rsplit_once('.')?;
+    Some((date.parse().ok()?, index.parse().ok()?))
+}
+
+fn latest_index(dir: &Path, date: NaiveDate) -> Option<u32> {
+    log_files(dir)
+        .iter()
+        .filter_map(|(_, stem)| parse_stem(stem))
+        .filter(|(file_date, _)| *file_date == date)
+        .map(|(_, index)| index)
+        .max()
+}
+
+fn prune(dir: &Path, keep: usize) -> Vec<String> {
+    let mut files: Vec<(PathBuf, (NaiveDate, u32))> = log_files(dir)
+        .into_iter()
+        .filter_map(|(path, stem)| Some((path, parse_stem(&stem)?)))
+        .collect();
+    files.sort_by_key(|(_, key)| std::cmp::Reverse(*key));
+
+    files
+        .into_iter()
+        .skip(keep.max(1))
+        .filter_map(|(path, _)| {
+            fs::remove_file(&path)
+                .err()
+                .map(|e| format!("failed to remove old log {}: {e}", path.display()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("message-client-{name}-{}", std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn date(text: &str) -> NaiveDate {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_stems() {
+        assert_eq!(parse_stem("2024-03-09.0"), Some((date("2024-03-09"), 0)));
+        assert_eq!(parse_stem("2024-03-09.12"), Some((date("2024-03-09"), 12)));
+        assert_eq!(parse_stem("2024-03-09"), None);
+        assert_eq!(parse_stem("2024-13-09.0"), None);
+        assert_eq!(parse_stem("2024-03-09.x"), None);
+    }
+
+    #[test]
+    fn prunes_the_oldest_files() {
+        let dir = temp_dir("prune");
+        for stem in [
+            "2024-03-08.0",
+            "2024-03-09.0",
+            "2024-03-09.1",
+            "2024-03-09.10",
+        ] {
+            File::create(dir.join(format!("{PREFIX}{stem}{SUFFIX}"))).unwrap();
+        }
+        File::create(dir.join("notes.txt")).unwrap();
+
+        assert!(prune(&dir, 2).is_empty());
+
+        let mut left: Vec<String> = log_files(&dir).into_iter().map(|(_, stem)| stem).collect();
+        left.sort();
+        assert_eq!(left, ["2024-03-09.1", "2024-03-09.10"]);
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(latest_index(&dir, date("2024-03-09")), Some(10));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn keeps_at_least_one_file_and_reports_failures() {
+        let dir = temp_dir("prune-errors");
+        File::create(dir.join(format!("{PREFIX}2024-03-09.0{SUFFIX}"))).unwrap();
+        // A directory can't be removed as a file, standing in for a permission problem.
+        fs::create_dir(dir.join(format!("{PREFIX}2024-03-08.0{SUFFIX}"))).unwrap();
+
+        let errors = prune(&dir, 0);
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("2024-03-08.0"));
+        assert!(dir.join(format!("{PREFIX}2024-03-09.0{SUFFIX}")).exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,196 @@
+use std::{io, net::IpAddr, path::PathBuf, time::Duration};
+
+use reqwest::{Certificate, Client, Identity, Proxy, Url};
+use thiserror::Error;
+
+pub const USER_AGENT: &str = concat!("message-client/", env!("CARGO_PKG_VERSION"));
+pub const DEFAULT_SERVER_URL: &str =
+    "https://ec2-44-250-68-143.us-west-2.compute.amazonaws.com:8000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Secure,
+    Local,
+    Plaintext,
+}
+
+pub struct ClientOptions {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    pub proxy: Option<String>,
+    pub ca_certs: Vec<PathBuf>,
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    pub user_agent: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("cannot read {0}: {1}")]
+    Read(PathBuf, #[source] io::Error),
+    #[error("invalid certificate in {0}: {1}")]
+    Certificate(PathBuf, #[source] reqwest::Error),
+    #[error("no certificates found in {0}")]
+    NoCertificates(PathBuf),
+    #[error("invalid client certificate or key: {0}")]
+    Identity(#[source] reqwest::Error),
+    #[error("--client-cert and --client-key must be given together")]
+    IncompleteIdentity,
+    #[error("invalid proxy {0}: {1}")]
+    Proxy(String, #[source] reqwest::Error),
+    #[error("cannot build HTTP client: {0}")]
+    Build(#[from] reqwest::Error),
+}
+
+impl ClientOptions {
+    pub fn build(&self) -> Result<Client, ClientError> {
+        let mut builder = Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.request_timeout)
+            .user_agent(&self.user_agent);
+
+        if let Some(proxy) = &self.proxy {
+            builder =
+                builder.proxy(Proxy::all(proxy).map_err(|e| ClientError::Proxy(proxy.clone(), e))?);
+        }
+
+        for path in &self.ca_certs {
+            let pem = read(path)?;
+            let certs = Certificate::from_pem_bundle(&pem)
+                .map_err(|e| ClientError::Certificate(path.clone(), e))?;
+            if certs.is_empty() {
+                return Err(ClientError::NoCertificates(path.clone()));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let identity = Identity::from_pkcs8_pem(&read(cert)?, &read(key)?)
+                    .map_err(ClientError::Identity)?;
+                builder = builder.identity(identity);
+            }
+            (None, None) => {}
+            _ => return Err(ClientError::IncompleteIdentity),
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+fn read(path: &PathBuf) -> Result<Vec<u8>, ClientError> {
+    std::fs::read(path).map_err(|e| ClientError::Read(path.clone(), e))
+}
+
+pub fn default_server_url() -> String {
+    std::env::var("BASE_URL").unwrap_or_else(|_| DEFAULT_SERVER_URL.to_string())
+}
+
+// Plain HTTP to the local machine never crosses the network, so it doesn't count as plaintext.
+pub fn transport(url: &str) -> Transport {
+    let Ok(url) = Url::parse(url) else {
+        return Transport::Plaintext;
+    };
+    if url.scheme() == "https" {
+        return Transport::Secure;
+    }
+
+    let local = url.host_str().is_some_and(|host| {
+        host == "localhost"
+            || host.ends_with(".localhost")
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    });
+    if local {
+        Transport::Local
+    } else {
+        Transport::Plaintext
+    }
+}
+
+pub fn host(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string())
+}
+
+// The scheme, host and port, with the port left out when it's the scheme's default.
+pub fn origin(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+        .filter(|origin| origin != "null")
+        .unwrap_or_else(|| url.to_string())
+}
+
+pub fn https_url(url: &str) -> Option<String> {
+    url.strip_prefix("http://")
+        .map(|rest| format!("https://{rest}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_transport() {
+        assert_eq!(transport("https://chat.example.com"), Transport::Secure);
+        assert_eq!(
+            transport("http://chat.example.com:8000"),
+            Transport::Plaintext
+        );
+        assert_eq!(transport("http://localhost:8000"), Transport::Local);
+        assert_eq!(transport("http://api.localhost"), Transport::Local);
+        assert_eq!(transport("http://127.0.0.1:8000"), Transport::Local);
+        assert_eq!(transport("http://[::1]:8000"), Transport::Local);
+        assert_eq!(transport("http://10.0.0.1"), Transport::Plaintext);
+        assert_eq!(
+            transport("http://localhost.example.com"),
+            Transport::Plaintext
+        );
+        assert_eq!(transport("not a url"), Transport::Plaintext);
+    }
+
+    #[test]
+    fn extracts_host() {
+        assert_eq!(
+            host("https://chat.example.com:8443/api"),
+            "chat.example.com"
+        );
+        assert_eq!(host("http://[::1]:8000"), "[::1]");
+        assert_eq!(host("not a url"), "not a url");
+    }
+
+    #[test]
+    fn origin_keeps_scheme_and_port() {
+        assert_eq!(
+            origin("https://chat.example.com/"),
+            "https://chat.example.com"
+        );
+        assert_eq!(
+            origin("https://chat.example.com:443"),
+            "https://chat.example.com"
+        );
+        assert_eq!(origin("http://localhost:8000/x"), "http://localhost:8000");
+        assert_ne!(
+            origin("http://localhost:8000"),
+            origin("http://localhost:9000")
+        );
+        assert_ne!(origin("http://host"), origin("https://host"));
+    }
+
+    #[test]
+    fn upgrades_only_plain_http() {
+        assert_eq!(
+            https_url("http://host:8000").as_deref(),
+            Some("https://host:8000")
+        );
+        assert_eq!(https_url("https://host"), None);
+    }
+}
@@ -16,41 +16,84 @@ pub enum InputEvent {
     NextField,
     PrevField,
     OpenConfig,
+    OpenEmojiPicker,
+    ToggleLogs,
+    ToggleNotifications,
+    Reconnect,
+    OpenAccounts,
+    ToggleMembers,
+    NextChannel,
+    PrevChannel,
+    FocusGained,
+    FocusLost,
 }
 
 pub async fn handle_input(input_tx: mpsc::UnboundedSender<InputEvent>) {
     let mut event_stream = EventStream::new();
 
     loop {
-        if let Some(Ok(Event::Key(key))) = event_stream.next().await {
-            let event = match key.code {
-                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(InputEvent::Quit)
-                }
-                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                    Some(InputEvent::OpenConfig)
-                }
-                KeyCode::Enter => Some(InputEvent::Submit),
-                KeyCode::Char(c) => Some(InputEvent::CharInput(c)),
-                KeyCode::Backspace => Some(InputEvent::Backspace),
-                KeyCode::Delete => Some(InputEvent::Delete),
-                KeyCode::Left => Some(InputEvent::CursorLeft),
-                KeyCode::Right => Some(InputEvent::CursorRight),
-                KeyCode::Up => Some(InputEvent::ScrollUp),
-                KeyCode::Down => Some(InputEvent::ScrollDown),
-                KeyCode::Esc => Some(InputEvent::Esc),
-                KeyCode::Tab => Some(InputEvent::NextField),
-                KeyCode::BackTab => Some(InputEvent::PrevField),
-                _ => None,
-            };
+        match event_stream.next().await {
+            Some(Ok(Event::Key(key))) => {
+                let event = match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::Quit)
+                    }
+                    KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::OpenConfig)
+                    }
+                    KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::OpenEmojiPicker)
+                    }
+                    KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::ToggleLogs)
+                    }
+                    KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::ToggleNotifications)
+                    }
+                    KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::Reconnect)
+                    }
+                    KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::OpenAccounts)
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::ToggleMembers)
+                    }
+                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::NextChannel)
+                    }
+                    KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        Some(InputEvent::PrevChannel)
+                    }
+                    KeyCode::Enter => Some(InputEvent::Submit),
+                    KeyCode::Char(c) => Some(InputEvent::CharInput(c)),
+                    KeyCode::Backspace => Some(InputEvent::Backspace),
+                    KeyCode::Delete => Some(InputEvent::Delete),
+                    KeyCode::Left => Some(InputEvent::CursorLeft),
+                    KeyCode::Right => Some(InputEvent::CursorRight),
+                    KeyCode::Up => Some(InputEvent::ScrollUp),
+                    KeyCode::Down => Some(InputEvent::ScrollDown),
+                    KeyCode::Esc => Some(InputEvent::Esc),
+                    KeyCode::Tab => Some(InputEvent::NextField),
+                    KeyCode::BackTab => Some(InputEvent::PrevField),
+                    _ => None,
+                };
 
-            if let Some(event) = event {
-                let should_quit = matches!(event, InputEvent::Quit);
-                let _ = input_tx.send(event);
-                if should_quit {
-                    break;
+                if let Some(event) = event {
+                    let should_quit = matches!(event, InputEvent::Quit);
+                    let _ = input_tx.send(event);
+                    if should_quit {
+                        break;
+                    }
                 }
             }
+            Some(Ok(Event::FocusGained)) => {
+                let _ = input_tx.send(InputEvent::FocusGained);
+            }
+            Some(Ok(Event::FocusLost)) => {
+                let _ = input_tx.send(InputEvent::FocusLost);
+            }
+            _ => {}
         }
     }
 }
@@ -0,0 +1,192 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+use crossterm::{
+    cursor::MoveTo,
+    queue,
+    style::{
+        Attribute, Color as CColor, Print, ResetColor, SetAttribute, SetBackgroundColor,
+        SetForegroundColor,
+    },
+};
+use tui::{buffer::Buffer, layout::Rect, style::Color};
+
+#[derive(PartialEq)]
+struct Placement {
+    x: u16,
+    y: u16,
+    url: String,
+    text: String,
+    fg: Color,
+    bg: Color,
+}
+
+pub struct Hyperlinks {
+    enabled: bool,
+    area: Rect,
+    last_written: Vec<Placement>,
+}
+
+impl Hyperlinks {
+    pub fn from_env() -> Self {
+        Self {
+            enabled: supports_hyperlinks(),
+            area: Rect::default(),
+            last_written: Vec::new(),
+        }
+    }
+
+    pub fn write(&mut self, buffer: &Buffer, area: Rect, urls: &[&str]) -> io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let positions = placements(buffer, area, urls);
+
+        // The terminal only repaints cells whose content changed, so links only
+        // need rewriting when their placement or colours differ from last frame.
+        if buffer.area == self.area && positions == self.last_written {
+            return Ok(());
+        }
+
+        let mut stdout = io::stdout();
+        for Placement {
+            x,
+            y,
+            url,
+            text,
+            fg,
+            bg,
+        } in &positions
+        {
+            queue!(
+                stdout,
+                MoveTo(*x, *y),
+                SetForegroundColor(terminal_color(*fg)),
+                SetBackgroundColor(terminal_color(*bg)),
+                SetAttribute(Attribute::Underlined),
+                Print(format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+        self.area = buffer.area;
+        self.last_written = positions;
+        stdout.flush()
+    }
+}
+
+// Long URLs wrap, so the rows are searched as one string and each row's part is linked.
+fn placements(buffer: &Buffer, area: Rect, urls: &[&str]) -> Vec<Placement> {
+    let mut text = String::new();
+    let mut cells = Vec::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            cells.push((text.len(), x, y));
+            text.push_str(&buffer.get(x, y).symbol);
+        }
+    }
+
+    let mut positions = Vec::new();
+    for url in urls {
+        for (start, _) in text.match_indices(url) {
+            let Ok(first) = cells.binary_search_by_key(&start, |(offset, ..)| *offset) else {
+                continue;
+            };
+            let end = start + url.len();
+            let mut segment: Option<Placement> = None;
+            for &(_, x, y) in cells[first..]
+                .iter()
+                .take_while(|(offset, ..)| *offset < end)
+            {
+                let cell = buffer.get(x, y);
+                match &mut segment {
+                    Some(placement) if placement.y == y => placement.text.push_str(&cell.symbol),
+                    _ => {
+                        positions.extend(segment.take());
+                        segment = Some(Placement {
+                            x,
+                            y,
+                            url: url.to_string(),
+                            text: cell.symbol.clone(),
+                            fg: cell.fg,
+                            bg: cell.bg,
+                        });
+                    }
+                }
+            }
+            positions.extend(segment);
+        }
+    }
+    positions
+}
+
+// tui's own conversion targets the crossterm version it was built against.
+fn terminal_color(color: Color) -> CColor {
+    match color {
+        Color::Reset => CColor::Reset,
+        Color::Black => CColor::Black,
+        Color::Red => CColor::DarkRed,
+        Color::Green => CColor::DarkGreen,
+        Color::Yellow => CColor::DarkYellow,
+        Color::Blue => CColor::DarkBlue,
+        Color::Magenta => CColor::DarkMagenta,
+        Color::Cyan => CColor::DarkCyan,
+        Color::Gray => CColor::Grey,
+        Color::DarkGray => CColor::DarkGrey,
+        Color::LightRed => CColor::Red,
+        Color::LightGreen => CColor::Green,
+        Color::LightBlue => CColor::Blue,
+        Color::LightYellow => CColor::Yellow,
+        Color::LightMagenta => CColor::Magenta,
+        Color::LightCyan => CColor::Cyan,
+        Color::White => CColor::White,
+        Color::Indexed(i) => CColor::AnsiValue(i),
+        Color::Rgb(r, g, b) => CColor::Rgb { r, g, b },
+    }
+}
+
+fn supports_hyperlinks() -> bool {
+    if let Ok(value) = std::env::var("MESSAGE_CLIENT_HYPERLINKS") {
+        return value == "1";
+    }
+
+    let env = |name: &str| std::env::var(name).unwrap_or_default();
+    let term_program = env("TERM_PROGRAM");
+    let term = env("TERM");
+
+    ["iTerm.app", "WezTerm", "vscode", "ghostty"].contains(&term_program.as_str())
+        || ["xterm-kitty", "foot", "alacritty"]
+            .iter()
+            .any(|prefix| term.starts_with(prefix))
+        || std::env::var_os("KITTY_WINDOW_ID").is_some()
+        || std::env::var_os("WT_SESSION").is_some()
+        || env("VTE_VERSION").parse::<u32>().is_ok_and(|v| v >= 5000)
+}
+
+pub fn open(opener: &str, url: &str) {
+    let mut parts = opener.split_whitespace();
+    let Some(program) = parts.next() else {
+        tracing::warn!("no link opener configured");
+        return;
+    };
+
+    let mut command = Command::new(program);
+    command
+        .args(parts)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    let url = url.to_string();
+    tokio::task::spawn_blocking(move || match command.status() {
+        Ok(status) if !status.success() => {
+            tracing::warn!("link opener exited with {status} for {url}")
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("failed to open {url}: {e}"),
+    });
+}
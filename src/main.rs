@@ -1,57 +1,72 @@
-use std::{cell::RefCell, rc::Rc, time::Duration};
+use std::{cell::RefCell, ops::ControlFlow, rc::Rc, time::Duration};
 
+use clap::Parser;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::sync::mpsc;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 use tui::{
     Terminal,
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Style},
     widgets::{Block, Borders},
 };
 
 use crate::{
-    components::{Config, ConnectionStatus, InputBox, MessageList, StatusBar},
-    network::AuthRequest,
-    state::{AppState, FocusedItem},
+    commands::Action,
+    input::InputEvent,
+    network::{NetworkError, NetworkRequest, NetworkResponse},
 };
 use crate::{
-    input::InputEvent,
-    network::{NetworkRequest, NetworkResponse},
+    components::{
+        AccountPicker, ChannelList, Config, ConnectionStatus, EmojiPicker, EmojiTarget, InputBox,
+        LinkPicker, LogViewer, Login, LoginAction, MemberList, MessageList, Register, Severity,
+        StatusBar, Toasts,
+    },
+    state::{AppState, FocusedItem, Upload},
 };
 
+mod account;
+mod backoff;
+mod cli;
+mod commands;
+mod completion;
 mod components;
+mod credentials;
+mod emoji;
+mod file_log;
+mod http;
 mod input;
+mod links;
 mod network;
+mod notify;
+mod paths;
 mod poll;
+mod read_state;
 mod state;
+mod text;
 mod tracing_writer;
 
-fn should_reconnect(app_state: &AppState) -> bool {
-    if let Some(token) = &app_state.session_token {
-        let valid = token.is_valid();
-        return !valid;
-    }
-
-    match app_state.last_reconnect {
-        None => true,
-        Some(last_attempt) => last_attempt.elapsed() >= app_state.reconnect_duration,
-    }
-}
+const TYPING_POLL_INTERVAL: Duration = Duration::from_secs(3);
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
+    let args = cli::Args::parse();
+    let client = match args.client_options().build() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("error: {e}");
+            std::process::exit(2);
+        }
+    };
     let app_state = Rc::new(RefCell::new(AppState::new()));
 
     let (input_tx, mut input_rx) = mpsc::unbounded_channel::<InputEvent>();
-    let (req_tx, req_rx) = mpsc::unbounded_channel::<NetworkRequest>();
-    let (resp_tx, mut resp_rx) = mpsc::unbounded_channel::<NetworkResponse>();
 
     let tracing_env_filter = EnvFilter::builder()
         .with_default_directive(if cfg!(debug_assertions) {
@@ -61,153 +76,592 @@ async fn main() -> std::io::Result<()> {
         })
         .from_env_lossy();
 
+    let file_writer = args
+        .log_file
+        .then(|| {
+            let dir = args
+                .log_dir
+                .clone()
+                .or_else(|| paths::state_dir().map(|dir| dir.join("logs")))?;
+            match file_log::RotatingWriter::new(
+                dir.clone(),
+                args.log_max_size.saturating_mul(1024 * 1024),
+                args.log_keep,
+            ) {
+                Ok(writer) => Some(writer),
+                Err(e) => {
+                    eprintln!(
+                        "file logging disabled, cannot write to {}: {e}",
+                        dir.display()
+                    );
+                    None
+                }
+            }
+        })
+        .flatten();
+
+    let file_log = file_writer.clone();
     let buffered_writer = tracing_writer::BufferedWriter::new();
     tracing_subscriber::registry()
         .with(
             fmt::layer()
                 .with_writer(buffered_writer.clone())
-                .with_ansi(true),
+                .with_ansi(false),
         )
+        .with(file_writer.map(|writer| fmt::layer().with_writer(writer).with_ansi(false)))
         .with(tracing_env_filter)
         .init();
 
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        tracing::error!("{info}");
+        default_hook(info);
+    }));
+
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal: Terminal<CrosstermBackend<std::io::Stdout>> = Terminal::new(backend)?;
 
     tokio::spawn(input::handle_input(input_tx));
-    tokio::spawn(async move {
-        network::NetworkTask::new().run(req_rx, resp_tx).await;
-    });
 
     let message_list = MessageList::new(app_state.clone());
     let input_box = InputBox::new(app_state.clone());
     let status_bar = StatusBar::new(app_state.clone());
+    let member_list = MemberList::new(app_state.clone());
+    let channel_list = ChannelList::new(app_state.clone());
     let mut config = Config::new();
+    let mut account_args = args.accounts.iter().cloned();
+    let first_name = match account_args.next() {
+        Some((name, server_url)) => {
+            config.server_url = server_url;
+            name
+        }
+        None => "default".to_string(),
+    };
+    let mut accounts = account::Accounts::new(
+        first_name,
+        &mut app_state.borrow_mut(),
+        &mut config,
+        client,
+        args.allow_plaintext,
+    );
+    for (name, server_url) in account_args {
+        accounts.add(name, server_url, &mut app_state.borrow_mut());
+    }
+    let mut link_picker = LinkPicker::new();
+    let mut account_picker = AccountPicker::new();
+    let mut register = Register::new();
+    let mut login = Login::new();
+    if config.username.is_empty() || config.password.is_empty() {
+        login.open(&config, None);
+        app_state.borrow_mut().focused_item = FocusedItem::Login;
+    }
+    let mut emoji_picker = EmojiPicker::new();
+    let mut log_viewer = LogViewer::new(buffered_writer.clone());
+    let mut toasts = Toasts::new(app_state.clone());
+    let notifier = notify::Notifier::from_env();
+    let mut hyperlinks = links::Hyperlinks::from_env();
 
-    let req_tx_messages = req_tx.clone();
-    let mut messages_poll = poll::Poll::new(Duration::from_secs(10), move || {
-        req_tx_messages.send(NetworkRequest::FetchMessages).ok();
-    });
+    let mut last_typing_fetch = tokio::time::Instant::now();
 
     loop {
-        terminal.draw(|f| {
+        let mut message_area = Rect::default();
+        let frame = terminal.draw(|f| {
             let size = f.size();
+            let log_height = if log_viewer.is_visible { 12 } else { 0 };
 
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([
-                    Constraint::Length(3), // Title bar
-                    Constraint::Min(10),   // Message history
-                    Constraint::Length(3), // Input box
-                    Constraint::Length(1), // Status bar
+                    Constraint::Length(3),          // Title bar
+                    Constraint::Min(10),            // Message history
+                    Constraint::Length(log_height), // Log pane
+                    Constraint::Length(3),          // Input box
+                    Constraint::Length(1),          // Status bar
                 ])
                 .split(size);
 
+            let unread = accounts.total_unread(&app_state.borrow());
+            let title = if unread > 0 {
+                format!("Message Client ({unread} unread)")
+            } else {
+                "Message Client".to_string()
+            };
             let title_block = Block::default()
-                .title("Message Client")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Blue));
             f.render_widget(title_block, chunks[0]);
 
+            let show_members = app_state.borrow().show_members;
+            let members_width = if show_members { 28 } else { 0 };
+            let main_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Length(24),            // Channels
+                    Constraint::Min(20),               // Messages
+                    Constraint::Length(members_width), // Members
+                ])
+                .split(chunks[1]);
+
+            message_area = main_chunks[1];
+            channel_list.render(f, main_chunks[0]);
+            message_list.render(f, main_chunks[1]);
+            if show_members {
+                member_list.render(f, main_chunks[2]);
+            }
+
+            if log_viewer.is_visible {
+                let focused = app_state.borrow().focused_item == FocusedItem::Logs;
+                log_viewer.render(f, chunks[2], focused);
+            }
+            input_box.render(f, chunks[3]);
+            status_bar.render(f, chunks[4]);
             config.render(f);
-            message_list.render(f, chunks[1]);
-            input_box.render(f, chunks[2]);
-            status_bar.render(f, chunks[3]);
+            link_picker.render(f);
+            account_picker.render(f);
+            register.render(f);
+            login.render(f);
+            emoji_picker.render(f);
+            toasts.render(f);
         })?;
 
+        {
+            let app_state = app_state.borrow();
+            let urls: Vec<&str> = app_state
+                .channel_messages()
+                .flat_map(|message| message.links())
+                .collect();
+            // Inside the borders, so a wrapped URL continues straight onto the next row.
+            let inner = message_area.inner(&Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            hyperlinks.write(frame.buffer, inner, &urls)?;
+        }
+
         let mut app_state = app_state.borrow_mut();
+        let req_tx = accounts.active().req_tx.clone();
         match input_rx.try_recv() {
             Ok(InputEvent::Quit) => {
                 break;
             }
-            Ok(InputEvent::Submit) => {
-                if let (Some(content), Some(session)) =
-                    (app_state.send_message(), app_state.session_token.clone())
-                {
-                    req_tx
-                        .send(NetworkRequest::SendMessage {
-                            content: content,
-                            session,
-                        })
-                        .ok();
-                };
-            }
+            Ok(InputEvent::Submit) => match app_state.focused_item {
+                FocusedItem::Main => match commands::parse(&app_state.input_buffer) {
+                    Some(command) => {
+                        app_state.clear_input();
+                        match command.and_then(|command| {
+                            commands::execute(command, &mut app_state, &mut config, &req_tx)
+                        }) {
+                            Ok(ControlFlow::Break(Action::Quit)) => break,
+                            Ok(ControlFlow::Break(Action::Login)) => {
+                                login.open(&config, None);
+                                app_state.focused_item = FocusedItem::Login;
+                            }
+                            Ok(ControlFlow::Break(Action::Register)) => {
+                                register.open(&config.server_url);
+                                app_state.focused_item = FocusedItem::Register;
+                            }
+                            Ok(ControlFlow::Break(Action::Account { name, server_url })) => {
+                                if let Err(e) =
+                                    accounts.select(name, server_url, &mut app_state, &mut config)
+                                {
+                                    app_state.system_message(e.to_string());
+                                }
+                            }
+                            Ok(ControlFlow::Continue(())) => {}
+                            Err(e) => app_state.system_message(e.to_string()),
+                        }
+                    }
+                    None => {
+                        if let Some(request) = app_state.send_message() {
+                            req_tx.send(request).ok();
+                        }
+                    }
+                },
+                FocusedItem::Members => {
+                    let own_id = app_state.session_token.as_ref().map(|t| t.user_id);
+                    let selected = MemberList::sorted_members(&app_state)
+                        .get(app_state.selected_member)
+                        .map(|(user, _)| user.id);
+                    if let Some(user_id) = selected
+                        && Some(user_id) != own_id
+                    {
+                        app_state.open_direct(user_id);
+                        app_state.focused_item = FocusedItem::Main;
+                    }
+                }
+                FocusedItem::Login => match login.submit() {
+                    Some(LoginAction::LogIn {
+                        username,
+                        password,
+                        server_url,
+                    }) => {
+                        config.username = username;
+                        config.password = password;
+                        if server_url != config.server_url {
+                            let server = commands::Command::Server(server_url);
+                            commands::execute(server, &mut app_state, &mut config, &req_tx).ok();
+                        } else {
+                            app_state.retry_connection();
+                        }
+                    }
+                    Some(LoginAction::Test(server_url)) => {
+                        req_tx.send(NetworkRequest::TestConnection(server_url)).ok();
+                    }
+                    None => {}
+                },
+                FocusedItem::Register => {
+                    if let Some(request) = register.submit() {
+                        req_tx.send(NetworkRequest::Register(request)).ok();
+                    }
+                }
+                FocusedItem::AccountPicker => {
+                    account_picker.close();
+                    app_state.focused_item = FocusedItem::Main;
+                    accounts.switch(account_picker.selected(), &mut app_state, &mut config);
+                }
+                FocusedItem::LinkPicker => {
+                    if let Some(url) = link_picker.selected() {
+                        links::open(&config.link_opener, url);
+                    }
+                    link_picker.close();
+                    app_state.focused_item = FocusedItem::Messages;
+                }
+                FocusedItem::EmojiPicker => {
+                    let emoji = emoji_picker.selected();
+                    emoji_picker.close();
+                    match emoji_picker.target() {
+                        EmojiTarget::Input => {
+                            if let Some(emoji) = emoji {
+                                app_state.insert_str(emoji);
+                            }
+                            app_state.focused_item = FocusedItem::Main;
+                        }
+                        EmojiTarget::Reaction => {
+                            if let Some(request) =
+                                emoji.and_then(|emoji| app_state.toggle_reaction(emoji))
+                            {
+                                req_tx.send(request).ok();
+                            }
+                            app_state.focused_item = FocusedItem::Messages;
+                        }
+                    }
+                }
+                FocusedItem::Messages
+                | FocusedItem::Config
+                | FocusedItem::Logs
+                | FocusedItem::Notifications => {}
+            },
             Ok(InputEvent::CharInput(c)) => match app_state.focused_item {
-                FocusedItem::Main => app_state.insert_char(c),
+                FocusedItem::Main => {
+                    app_state.insert_char(c);
+                    if let Some(request) = app_state.typing_request() {
+                        req_tx.send(request).ok();
+                    }
+                }
+                FocusedItem::Messages => match c {
+                    'o' => {
+                        let links: Vec<String> = app_state
+                            .selected_message()
+                            .map(|message| message.links().into_iter().map(String::from).collect())
+                            .unwrap_or_default();
+                        if !links.is_empty() {
+                            link_picker.open(links);
+                            app_state.focused_item = FocusedItem::LinkPicker;
+                        }
+                    }
+                    'e' => app_state.start_edit(),
+                    's' => {
+                        if let Some(session) = app_state.session_token.clone()
+                            && let Some(message) = app_state.selected_message()
+                        {
+                            for attachment in &message.attachments {
+                                req_tx
+                                    .send(NetworkRequest::DownloadAttachment {
+                                        attachment: attachment.clone(),
+                                        directory: config.download_dir.clone().into(),
+                                        session: session.clone(),
+                                    })
+                                    .ok();
+                            }
+                        }
+                    }
+                    'r' if app_state.selected_message().is_some() => {
+                        emoji_picker.open(EmojiTarget::Reaction);
+                        app_state.focused_item = FocusedItem::EmojiPicker;
+                    }
+                    'd' => {
+                        if let Some(request) = app_state.delete_selected() {
+                            req_tx.send(request).ok();
+                        }
+                    }
+                    _ => app_state.pending_delete = None,
+                },
                 FocusedItem::Config => config.insert_char(c),
+                FocusedItem::Register => register.insert_char(c),
+                FocusedItem::Login => login.insert_char(c),
+                FocusedItem::EmojiPicker => emoji_picker.insert_char(c),
+                FocusedItem::Logs if c == 'G' => log_viewer.follow(),
+                FocusedItem::Logs => log_viewer.set_level(c),
+                _ => {}
             },
             Ok(InputEvent::Backspace) => match app_state.focused_item {
                 FocusedItem::Main => app_state.backspace(),
                 FocusedItem::Config => config.backspace(),
+                FocusedItem::Register => register.backspace(),
+                FocusedItem::Login => login.backspace(),
+                FocusedItem::EmojiPicker => emoji_picker.backspace(),
+                _ => {}
             },
             Ok(InputEvent::Delete) => match app_state.focused_item {
                 FocusedItem::Main => app_state.delete_char(),
+                FocusedItem::Messages => {
+                    if let Some(request) = app_state.delete_selected() {
+                        req_tx.send(request).ok();
+                    }
+                }
                 FocusedItem::Config => config.delete_char(),
+                FocusedItem::Register => register.delete_char(),
+                FocusedItem::Login => login.delete_char(),
+                _ => {}
             },
             Ok(InputEvent::CursorLeft) => match app_state.focused_item {
                 FocusedItem::Main => app_state.move_cursor_left(),
                 FocusedItem::Config => config.move_cursor_left(),
+                FocusedItem::Register => register.move_cursor_left(),
+                FocusedItem::Login => login.move_cursor_left(),
+                _ => {}
             },
             Ok(InputEvent::CursorRight) => match app_state.focused_item {
                 FocusedItem::Main => app_state.move_cursor_right(),
                 FocusedItem::Config => config.move_cursor_right(),
+                FocusedItem::Register => register.move_cursor_right(),
+                FocusedItem::Login => login.move_cursor_right(),
+                _ => {}
+            },
+            Ok(InputEvent::ScrollUp) => match app_state.focused_item {
+                FocusedItem::Messages => app_state.select_previous_message(),
+                FocusedItem::LinkPicker => link_picker.previous(),
+                FocusedItem::AccountPicker => account_picker.previous(),
+                FocusedItem::EmojiPicker => emoji_picker.previous(),
+                FocusedItem::Logs => log_viewer.scroll_up(),
+                FocusedItem::Notifications => toasts.scroll_up(),
+                FocusedItem::Members => {
+                    app_state.selected_member = app_state.selected_member.saturating_sub(1)
+                }
+                _ => app_state.scroll_up(),
+            },
+            Ok(InputEvent::ScrollDown) => match app_state.focused_item {
+                FocusedItem::Messages => app_state.select_next_message(),
+                FocusedItem::LinkPicker => link_picker.next(),
+                FocusedItem::AccountPicker => account_picker.next(),
+                FocusedItem::EmojiPicker => emoji_picker.next(),
+                FocusedItem::Logs => log_viewer.scroll_down(),
+                FocusedItem::Notifications => toasts.scroll_down(),
+                FocusedItem::Members => {
+                    let last = app_state.users.len().saturating_sub(1);
+                    app_state.selected_member = (app_state.selected_member + 1).min(last)
+                }
+                _ => app_state.scroll_down(),
             },
-            Ok(InputEvent::ScrollUp) => app_state.scroll_up(),
-            Ok(InputEvent::ScrollDown) => app_state.scroll_down(),
             Ok(InputEvent::Esc) => match app_state.focused_item {
+                FocusedItem::Main if app_state.completion.is_some() => app_state.completion = None,
+                FocusedItem::Main if app_state.has_active_toasts() => app_state.dismiss_toasts(),
                 FocusedItem::Main => app_state.clear_input(),
+                FocusedItem::Messages if app_state.pending_delete.is_some() => {
+                    app_state.pending_delete = None
+                }
+                FocusedItem::Messages | FocusedItem::Members | FocusedItem::Logs => {
+                    app_state.focused_item = FocusedItem::Main
+                }
+                FocusedItem::LinkPicker => {
+                    link_picker.close();
+                    app_state.focused_item = FocusedItem::Messages;
+                }
+                FocusedItem::AccountPicker => {
+                    account_picker.close();
+                    app_state.focused_item = FocusedItem::Main;
+                }
+                FocusedItem::EmojiPicker => {
+                    emoji_picker.close();
+                    app_state.focused_item = match emoji_picker.target() {
+                        EmojiTarget::Input => FocusedItem::Main,
+                        EmojiTarget::Reaction => FocusedItem::Messages,
+                    };
+                }
                 FocusedItem::Config => {
                     config.close();
                     app_state.focused_item = FocusedItem::Main;
+                    if config.server_url != app_state.server_url {
+                        let server = commands::Command::Server(config.server_url.clone());
+                        commands::execute(server, &mut app_state, &mut config, &req_tx).ok();
+                    } else if matches!(
+                        app_state.connection_status,
+                        ConnectionStatus::Failed | ConnectionStatus::Backoff { .. }
+                    ) {
+                        app_state.retry_connection();
+                    }
+                }
+                FocusedItem::Register => {
+                    register.close();
+                    app_state.focused_item = FocusedItem::Main;
+                }
+                FocusedItem::Login => {
+                    login.close();
+                    app_state.focused_item = FocusedItem::Main;
+                }
+                FocusedItem::Notifications => {
+                    toasts.close_history();
+                    app_state.focused_item = FocusedItem::Main;
                 }
             },
+            Ok(InputEvent::OpenEmojiPicker) if app_state.focused_item == FocusedItem::Main => {
+                app_state.completion = None;
+                emoji_picker.open(EmojiTarget::Input);
+                app_state.focused_item = FocusedItem::EmojiPicker;
+            }
+            Ok(InputEvent::OpenEmojiPicker) => {}
+            Ok(InputEvent::ToggleLogs) => {
+                log_viewer.toggle();
+                if log_viewer.is_visible {
+                    app_state.focused_item = FocusedItem::Logs;
+                } else if app_state.focused_item == FocusedItem::Logs {
+                    app_state.focused_item = FocusedItem::Main;
+                }
+            }
+            Ok(InputEvent::ToggleNotifications) => {
+                if toasts.is_visible {
+                    toasts.close_history();
+                    app_state.focused_item = FocusedItem::Main;
+                } else if matches!(
+                    app_state.focused_item,
+                    FocusedItem::Main
+                        | FocusedItem::Messages
+                        | FocusedItem::Members
+                        | FocusedItem::Logs
+                ) {
+                    app_state.dismiss_toasts();
+                    toasts.open_history();
+                    app_state.focused_item = FocusedItem::Notifications;
+                }
+            }
+            Ok(InputEvent::Reconnect) => {
+                if app_state.connection_status != ConnectionStatus::Connecting {
+                    app_state.retry_connection();
+                }
+            }
+            Ok(InputEvent::OpenAccounts)
+                if matches!(
+                    app_state.focused_item,
+                    FocusedItem::Main | FocusedItem::Messages | FocusedItem::Members
+                ) =>
+            {
+                account_picker.open(accounts.summaries(&app_state), accounts.active_index());
+                app_state.focused_item = FocusedItem::AccountPicker;
+            }
+            Ok(InputEvent::OpenAccounts) => {}
+            Ok(InputEvent::ToggleMembers) => app_state.toggle_members(),
             Ok(InputEvent::OpenConfig) => {
                 app_state.focused_item = FocusedItem::Config;
                 config.open();
             }
+            Ok(InputEvent::NextField)
+                if matches!(app_state.focused_item, FocusedItem::Main)
+                    && app_state.completion.is_some() =>
+            {
+                app_state.complete()
+            }
             Ok(InputEvent::NextField) => match app_state.focused_item {
-                FocusedItem::Main => {}
                 FocusedItem::Config => config.next_field(),
+                FocusedItem::Register => register.next_field(),
+                FocusedItem::Login => login.next_field(),
+                FocusedItem::LinkPicker => link_picker.next(),
+                FocusedItem::AccountPicker => account_picker.next(),
+                FocusedItem::EmojiPicker => emoji_picker.next(),
+                _ => app_state.cycle_focus(true),
             },
             Ok(InputEvent::PrevField) => match app_state.focused_item {
-                FocusedItem::Main => {}
                 FocusedItem::Config => config.previous_field(),
+                FocusedItem::Register => register.previous_field(),
+                FocusedItem::Login => login.previous_field(),
+                FocusedItem::LinkPicker => link_picker.previous(),
+                FocusedItem::AccountPicker => account_picker.previous(),
+                FocusedItem::EmojiPicker => emoji_picker.previous(),
+                _ => app_state.cycle_focus(false),
             },
+            Ok(InputEvent::NextChannel) => app_state.cycle_channel(true),
+            Ok(InputEvent::PrevChannel) => app_state.cycle_channel(false),
+            Ok(InputEvent::FocusGained) => {
+                app_state.terminal_focused = true;
+                if app_state.unread_divider.is_none() {
+                    app_state.update_unread_divider();
+                }
+            }
+            Ok(InputEvent::FocusLost) => app_state.terminal_focused = false,
             Err(mpsc::error::TryRecvError::Empty) => {}
             Err(mpsc::error::TryRecvError::Disconnected) => {
                 break;
             }
         }
 
-        if app_state.connection_status == ConnectionStatus::Disconnected
-            && should_reconnect(&app_state)
-        {
-            req_tx
-                .send(NetworkRequest::Authenticate(AuthRequest {
-                    name: config.username.clone(),
-                    password: config.password.clone(),
-                }))
-                .ok();
-            app_state.last_reconnect = Some(tokio::time::Instant::now())
+        // The input may have switched accounts, and these requests carry the session of
+        // whichever one is active now, so they must go to its server.
+        let req_tx = accounts.active().req_tx.clone();
+        if let Some(request) = app_state.mark_read() {
+            req_tx.send(request).ok();
         }
 
-        messages_poll.poll();
+        if last_typing_fetch.elapsed() >= TYPING_POLL_INTERVAL
+            && let Some(session) = app_state.session_token.clone()
+        {
+            req_tx.send(NetworkRequest::FetchTyping(session)).ok();
+            last_typing_fetch = tokio::time::Instant::now();
+        }
 
-        match resp_rx.try_recv() {
-            Ok(NetworkResponse::Auth(token)) => {
-                app_state.update_session(Some(token));
-                req_tx.send(NetworkRequest::FetchMessages).ok();
-            }
-            Ok(NetworkResponse::Error(e)) => {
-                tracing::warn!("{e:?}")
+        accounts.poll();
+        for index in 0..accounts.len() {
+            if let Some(response) = accounts.try_recv(index) {
+                accounts.with_account(
+                    index,
+                    &mut app_state,
+                    &mut config,
+                    |app_state, config, account| {
+                        handle_response(
+                            response,
+                            app_state,
+                            config,
+                            &mut register,
+                            &mut login,
+                            &account.req_tx,
+                            &notifier,
+                        )
+                    },
+                );
             }
-            Ok(NetworkResponse::MessagesReceived(messages)) => app_state.messages = messages,
-            Ok(_) => {}
-            Err(TryRecvError::Empty) => {}
-            Err(TryRecvError::Disconnected) => {}
+
+            accounts.with_account(
+                index,
+                &mut app_state,
+                &mut config,
+                |app_state, config, account| {
+                    if let Some(request) =
+                        app_state.poll_connection(&config.username, &config.password)
+                    {
+                        account
+                            .req_tx
+                            .send(NetworkRequest::Authenticate(request))
+                            .ok();
+                    }
+                },
+            );
         }
     }
 
@@ -215,13 +669,189 @@ async fn main() -> std::io::Result<()> {
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
-        DisableMouseCapture
+        DisableMouseCapture,
+        DisableFocusChange
     )?;
 
     let logs = buffered_writer.get_contents();
     if !logs.is_empty() {
         eprintln!("{}", logs)
     }
+    for error in file_log.iter().flat_map(|writer| writer.take_errors()) {
+        eprintln!("{error}");
+    }
 
     Ok(())
 }
+
+fn handle_response(
+    response: NetworkResponse,
+    app_state: &mut AppState,
+    config: &mut Config,
+    register: &mut Register,
+    login: &mut Login,
+    req_tx: &mpsc::UnboundedSender<NetworkRequest>,
+    notifier: &notify::Notifier,
+) {
+    match response {
+        NetworkResponse::Auth(token) => {
+            for request in app_state.connection_established(token) {
+                req_tx.send(request).ok();
+            }
+            if app_state.background_account.is_none() {
+                match login.succeeded() {
+                    Some(true) => credentials::remember(
+                        &config.server_url,
+                        credentials::SavedLogin {
+                            username: config.username.clone(),
+                            password: config.password.clone(),
+                        },
+                    ),
+                    Some(false) => credentials::forget(&config.server_url),
+                    None => {}
+                }
+                if app_state.focused_item == FocusedItem::Login {
+                    login.close();
+                    app_state.focused_item = FocusedItem::Main;
+                }
+            }
+            if http::transport(&app_state.server_url) == http::Transport::Plaintext {
+                app_state.notify(
+                    Severity::Warning,
+                    "Connected over unencrypted HTTP, your password and messages can be read on the network".to_string(),
+                );
+            }
+            req_tx.send(NetworkRequest::FetchMessages).ok();
+            req_tx.send(NetworkRequest::FetchUsers).ok();
+        }
+        NetworkResponse::ServerUpgraded(url) => {
+            app_state.notify(
+                Severity::Info,
+                format!("Server supports HTTPS, using {url}"),
+            );
+            config.server_url = url.clone();
+            app_state.server_url = url;
+        }
+        // The login that follows arrives as a normal Auth response.
+        NetworkResponse::Registered(request) => {
+            if app_state.focused_item == FocusedItem::Register {
+                app_state.focused_item = FocusedItem::Main;
+            }
+            register.close();
+            app_state.notify(
+                Severity::Info,
+                format!("Registered as {}, logging in", request.name),
+            );
+            config.username = request.name;
+            config.password = request.password;
+            // Otherwise poll_connection would start a second login with the new credentials.
+            app_state.connection_status = ConnectionStatus::Connecting;
+        }
+        NetworkResponse::RegistrationFailed(e) => {
+            tracing::warn!("{e:?}");
+            if app_state.focused_item == FocusedItem::Register
+                && app_state.background_account.is_none()
+            {
+                register.failed(&e);
+            } else {
+                app_state.notify(Severity::Error, e.user_message());
+            }
+        }
+        NetworkResponse::ConnectionTested { server_url, result }
+            if app_state.background_account.is_none() =>
+        {
+            login.tested(&server_url, result.map_err(|e| e.user_message()))
+        }
+        NetworkResponse::SessionExpired(request) => {
+            if let Some(request) = app_state.session_expired(request) {
+                req_tx.send(request).ok();
+            }
+        }
+        NetworkResponse::Error(e) => {
+            tracing::warn!("{e:?}");
+            let failed = match app_state.connection_status {
+                ConnectionStatus::Connecting => matches!(e, NetworkError::Auth(_)),
+                ConnectionStatus::Connected => e.is_connection_lost(),
+                _ => false,
+            };
+            if failed {
+                app_state.connection_failed(&e);
+            }
+
+            // Login problems for the active account belong in the login view, opening it
+            // when the server turned the credentials down.
+            let can_open = matches!(
+                app_state.focused_item,
+                FocusedItem::Main | FocusedItem::Messages | FocusedItem::Members
+            );
+            if failed && app_state.background_account.is_none() {
+                if app_state.focused_item == FocusedItem::Login {
+                    login.failed(e.user_message());
+                    return;
+                }
+                if e.is_unauthorized() && can_open {
+                    login.open(config, Some(e.user_message()));
+                    app_state.focused_item = FocusedItem::Login;
+                    return;
+                }
+            }
+            app_state.notify(Severity::Error, e.user_message());
+        }
+        NetworkResponse::MessagesReceived(messages) => {
+            let new_messages = app_state.set_messages(false, messages);
+            notifier.notify_messages(app_state, config, &new_messages);
+            if let Some(session) = app_state.session_token.clone() {
+                req_tx
+                    .send(NetworkRequest::FetchDirectMessages(session))
+                    .ok();
+            }
+        }
+        NetworkResponse::DirectMessagesReceived(messages) => {
+            let new_messages = app_state.set_messages(true, messages);
+            notifier.notify_messages(app_state, config, &new_messages);
+        }
+        NetworkResponse::UsersReceived(users) => app_state.set_users(users),
+        NetworkResponse::MessageEdited {
+            id,
+            direct,
+            content,
+            edited_at,
+        } => app_state.apply_edit(id, direct, content, edited_at),
+        NetworkResponse::MessageDeleted { id, direct } => app_state.remove_message(id, direct),
+        NetworkResponse::UploadProgress {
+            filename,
+            sent,
+            total,
+        } => {
+            app_state.upload = Some(Upload {
+                filename,
+                sent,
+                total,
+            })
+        }
+        NetworkResponse::UploadFinished(filename) => {
+            app_state.upload = None;
+            app_state.system_message(format!("Uploaded {filename}"));
+            req_tx.send(NetworkRequest::FetchMessages).ok();
+        }
+        NetworkResponse::UploadFailed { filename, error } => {
+            app_state.upload = None;
+            app_state.notify(
+                Severity::Warning,
+                format!("Failed to upload {filename}: {error}"),
+            );
+        }
+        NetworkResponse::TypingReceived(typing) => app_state.set_typing(typing),
+        NetworkResponse::AttachmentSaved(path) => {
+            app_state.notify(Severity::Info, format!("Saved {}", path.display()))
+        }
+        NetworkResponse::ReactionChanged {
+            message_id,
+            direct,
+            emoji,
+            user_id,
+            added,
+        } => app_state.apply_reaction(message_id, direct, emoji, user_id, added),
+        _ => {}
+    }
+}
@@ -1,19 +1,28 @@
 use std::{
     collections::HashMap,
+    path::{Path, PathBuf},
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use chrono::{DateTime, Utc};
-use reqwest::Response;
+use futures::StreamExt;
+use reqwest::{
+    Body, Response, StatusCode,
+    multipart::{Form, Part},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use thiserror::Error;
-use tokio::sync::mpsc;
+use tokio::{io::AsyncWriteExt, sync::mpsc, time::Instant};
+use tokio_util::io::ReaderStream;
 
-use crate::components::{Message, MessageSender};
+use crate::{
+    components::{Attachment, Channel, Message, MessageSender, Presence, Reaction},
+    http::{self, Transport},
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[allow(dead_code)]
 pub struct Token {
     pub token: String,
@@ -29,7 +38,7 @@ impl Token {
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        return now >= self.expiry;
+        now < self.expiry
     }
 }
 
@@ -42,30 +51,206 @@ pub struct ServerMessage {
     pub in_reply_to: Option<u32>,
     pub channel: String,
     pub created_at: DateTime<Utc>,
+    #[serde(default, alias = "updated_at")]
+    pub edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reactions: Vec<ServerReaction>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
 }
 
 #[derive(Deserialize)]
-struct User {
-    id: u32,
-    name: String,
+pub struct ServerReaction {
+    pub emoji: String,
+    pub user_id: u32,
 }
 
+#[derive(Deserialize)]
+pub struct ServerDirectMessage {
+    pub id: u32,
+    pub body: String,
+    pub sender_id: u32,
+    pub recipient_id: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, alias = "updated_at")]
+    pub edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reactions: Vec<ServerReaction>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Deserialize)]
+pub struct ServerTyping {
+    pub user_id: u32,
+    pub channel: Option<String>,
+    pub recipient_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypingUser {
+    pub user_id: u32,
+    pub channel: Channel,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: u32,
+    pub name: String,
+    #[serde(default, alias = "status")]
+    pub presence: Option<Presence>,
+}
+
+#[derive(Clone)]
 #[allow(dead_code)]
 pub enum NetworkRequest {
     Authenticate(AuthRequest),
+    Register(AuthRequest),
+    TestConnection(String),
     SendMessage {
         content: String,
+        channel: String,
+        session: Arc<Token>,
+    },
+    SendDirectMessage {
+        content: String,
+        recipient_id: u32,
+        session: Arc<Token>,
+    },
+    EditMessage {
+        id: u32,
+        content: String,
+        direct: bool,
+        session: Arc<Token>,
+    },
+    DeleteMessage {
+        id: u32,
+        direct: bool,
+        session: Arc<Token>,
+    },
+    AddReaction {
+        message_id: u32,
+        emoji: String,
+        direct: bool,
+        session: Arc<Token>,
+    },
+    RemoveReaction {
+        message_id: u32,
+        emoji: String,
+        direct: bool,
+        session: Arc<Token>,
+    },
+    UploadAttachment {
+        path: PathBuf,
+        channel: Channel,
+        session: Arc<Token>,
+    },
+    DownloadAttachment {
+        attachment: Attachment,
+        directory: PathBuf,
+        session: Arc<Token>,
+    },
+    Typing {
+        channel: Channel,
+        session: Arc<Token>,
+    },
+    FetchTyping(Arc<Token>),
+    MarkRead {
+        channel: Channel,
+        message_id: u32,
         session: Arc<Token>,
     },
     FetchMessages,
+    FetchDirectMessages(Arc<Token>),
+    FetchUsers,
+    UpdateProfile {
+        name: String,
+        session: Arc<Token>,
+    },
+    SetServer(String),
     RefreshToken,
 }
 
+impl NetworkRequest {
+    pub fn session_mut(&mut self) -> Option<&mut Arc<Token>> {
+        match self {
+            NetworkRequest::SendMessage { session, .. }
+            | NetworkRequest::SendDirectMessage { session, .. }
+            | NetworkRequest::EditMessage { session, .. }
+            | NetworkRequest::DeleteMessage { session, .. }
+            | NetworkRequest::AddReaction { session, .. }
+            | NetworkRequest::RemoveReaction { session, .. }
+            | NetworkRequest::UploadAttachment { session, .. }
+            | NetworkRequest::DownloadAttachment { session, .. }
+            | NetworkRequest::Typing { session, .. }
+            | NetworkRequest::FetchTyping(session)
+            | NetworkRequest::MarkRead { session, .. }
+            | NetworkRequest::FetchDirectMessages(session)
+            | NetworkRequest::UpdateProfile { session, .. } => Some(session),
+            NetworkRequest::Authenticate(_)
+            | NetworkRequest::Register(_)
+            | NetworkRequest::TestConnection(_)
+            | NetworkRequest::FetchMessages
+            | NetworkRequest::FetchUsers
+            | NetworkRequest::SetServer(_)
+            | NetworkRequest::RefreshToken => None,
+        }
+    }
+
+    pub fn with_session(mut self, token: Arc<Token>) -> Self {
+        if let Some(session) = self.session_mut() {
+            *session = token;
+        }
+        self
+    }
+}
+
 #[allow(dead_code)]
 pub enum NetworkResponse {
     Auth(Arc<Token>),
+    // The server rejected the session token, the request is handed back so it can be
+    // re-sent once a new session is established.
+    SessionExpired(NetworkRequest),
+    ServerUpgraded(String),
+    Registered(AuthRequest),
+    RegistrationFailed(NetworkError),
+    ConnectionTested {
+        server_url: String,
+        result: Result<Duration, NetworkError>,
+    },
     MessageSent,
+    MessageEdited {
+        id: u32,
+        direct: bool,
+        content: String,
+        edited_at: DateTime<Utc>,
+    },
+    MessageDeleted {
+        id: u32,
+        direct: bool,
+    },
+    ReactionChanged {
+        message_id: u32,
+        direct: bool,
+        emoji: String,
+        user_id: u32,
+        added: bool,
+    },
+    UploadProgress {
+        filename: String,
+        sent: u64,
+        total: u64,
+    },
+    UploadFinished(String),
+    UploadFailed {
+        filename: String,
+        error: NetworkError,
+    },
+    AttachmentSaved(PathBuf),
+    TypingReceived(Vec<TypingUser>),
     MessagesReceived(Vec<Message>),
+    DirectMessagesReceived(Vec<Message>),
+    UsersReceived(Vec<User>),
     Error(NetworkError),
 }
 
@@ -77,6 +262,8 @@ pub enum NetworkError {
     Deserialize(#[source] reqwest::Error),
     #[error("error authenticating: {0}")]
     Auth(#[from] AuthError),
+    #[error("error accessing file: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug, Error)]
@@ -86,29 +273,163 @@ pub(crate) enum AuthError {
     #[error("error deserializing response")]
     Deserialize(#[from] serde_json::Error),
     #[error("error with response status: {status}, body: {body}")]
-    Status { status: String, body: String },
+    Status { status: StatusCode, body: String },
+    #[error("username {0} is already taken")]
+    UsernameTaken(String),
+    #[error("refusing to send credentials over plain HTTP to {0}")]
+    Plaintext(String),
 }
 
-#[derive(Serialize, Deserialize)]
+impl NetworkError {
+    pub fn is_auth_rejected(&self) -> bool {
+        matches!(
+            self,
+            NetworkError::Auth(
+                AuthError::Status {
+                    status: StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN,
+                    ..
+                } | AuthError::Plaintext(_)
+            )
+        )
+    }
+
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(
+            self,
+            NetworkError::Auth(AuthError::Status {
+                status: StatusCode::UNAUTHORIZED,
+                ..
+            })
+        )
+    }
+
+    pub fn is_username_taken(&self) -> bool {
+        matches!(self, NetworkError::Auth(AuthError::UsernameTaken(_)))
+    }
+
+    pub fn is_session_expired(&self) -> bool {
+        matches!(
+            self,
+            NetworkError::RequestError(e)
+                if matches!(e.status(), Some(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN))
+        )
+    }
+
+    // Errors that mean the server can't be reached at all, as opposed to a failed request.
+    pub fn is_connection_lost(&self) -> bool {
+        match self {
+            NetworkError::RequestError(e) | NetworkError::Auth(AuthError::Request(e)) => {
+                e.is_connect() || e.is_timeout()
+            }
+            _ => false,
+        }
+    }
+
+    pub fn user_message(&self) -> String {
+        match self {
+            NetworkError::RequestError(e) | NetworkError::Auth(AuthError::Request(e)) => {
+                request_message(e)
+            }
+            NetworkError::Deserialize(_) | NetworkError::Auth(AuthError::Deserialize(_)) => {
+                "The server sent a response this client doesn't understand".to_string()
+            }
+            NetworkError::Auth(AuthError::Status { status, body }) => match *status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    "Login failed: invalid username or password".to_string()
+                }
+                status => match server_detail(body) {
+                    Some(detail) => format!("The server refused the request ({status}): {detail}"),
+                    None => format!("The server refused the request ({status})"),
+                },
+            },
+            NetworkError::Auth(AuthError::UsernameTaken(name)) => {
+                format!("The username {name} is already taken")
+            }
+            NetworkError::Auth(AuthError::Plaintext(host)) => format!(
+                "Not logging in: {host} uses unencrypted HTTP. Use an https:// server or start with --allow-plaintext"
+            ),
+            NetworkError::Io(e) => format!("File error: {e}"),
+        }
+    }
+}
+
+// Requests made with a rejected session are handed back instead of reported as errors.
+fn error_response(error: NetworkError, retry: &Option<NetworkRequest>) -> NetworkResponse {
+    match retry {
+        Some(request) if error.is_session_expired() => {
+            NetworkResponse::SessionExpired(request.clone())
+        }
+        _ => NetworkResponse::Error(error),
+    }
+}
+
+fn request_message(error: &reqwest::Error) -> String {
+    if error.is_connect() {
+        "Cannot reach the server, check the server URL and your connection".to_string()
+    } else if error.is_timeout() {
+        "The server took too long to respond".to_string()
+    } else if let Some(status) = error.status() {
+        format!("The server rejected the request ({status})")
+    } else {
+        format!("Network error: {error}")
+    }
+}
+
+// Servers usually wrap the reason in a JSON object, fall back to the raw text otherwise.
+fn server_detail(body: &str) -> Option<String> {
+    let body = body.trim();
+    if body.is_empty() {
+        return None;
+    }
+
+    let detail = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| {
+            ["detail", "error", "message"]
+                .iter()
+                .find_map(|key| value.get(key)?.as_str().map(str::to_string))
+        })
+        .unwrap_or_else(|| body.to_string());
+    Some(detail.chars().take(200).collect())
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AuthRequest {
     pub name: String,
     pub password: String,
 }
 
+const UNKNOWN_USERS_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+const TYPING_THROTTLE: Duration = Duration::from_secs(3);
+// Attachments can be large, so transfers aren't held to the normal request timeout.
+const UPGRADE_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const TRANSFER_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
 pub struct NetworkTask {
     client: reqwest::Client,
     base_url: String,
     users_map: HashMap<u32, Arc<str>>,
+    last_users_fetch: Option<Instant>,
+    typing_supported: bool,
+    read_sync_supported: bool,
+    last_typing_sent: Option<(Channel, Instant)>,
+    allow_plaintext: bool,
+    upgrade_checked: bool,
 }
 
 impl NetworkTask {
-    pub fn new() -> Self {
+    pub fn new(client: reqwest::Client, allow_plaintext: bool) -> Self {
         Self {
-            client: reqwest::Client::new(),
-            base_url: std::env::var("BASE_URL").unwrap_or(
-                "http://ec2-44-250-68-143.us-west-2.compute.amazonaws.com:8000".to_string(),
-            ),
+            client,
+            base_url: http::default_server_url(),
             users_map: HashMap::new(),
+            last_users_fetch: None,
+            typing_supported: true,
+            read_sync_supported: true,
+            last_typing_sent: None,
+            allow_plaintext,
+            upgrade_checked: false,
         }
     }
 
@@ -118,58 +439,464 @@ impl NetworkTask {
         resp_tx: mpsc::UnboundedSender<NetworkResponse>,
     ) {
         while let Some(req) = req_rx.recv().await {
+            let mut copy = req.clone();
+            let retry = copy.session_mut().is_some().then_some(copy);
+
             match req {
-                NetworkRequest::Authenticate(auth_req) => match self.auth(&auth_req).await {
-                    Ok(token) => {
-                        resp_tx.send(NetworkResponse::Auth(token.clone())).ok();
+                NetworkRequest::Authenticate(auth_req) => {
+                    if let Some(base_url) = self.upgrade_to_https().await {
+                        resp_tx.send(NetworkResponse::ServerUpgraded(base_url)).ok();
+                    }
+
+                    match self.auth(&auth_req).await {
+                        Ok(token) => {
+                            resp_tx.send(NetworkResponse::Auth(token.clone())).ok();
+                        }
+                        Err(e) => {
+                            resp_tx
+                                .send(NetworkResponse::Error(NetworkError::Auth(e)))
+                                .ok();
+                        }
+                    }
+                }
+                NetworkRequest::Register(auth_req) => {
+                    if let Some(base_url) = self.upgrade_to_https().await {
+                        resp_tx.send(NetworkResponse::ServerUpgraded(base_url)).ok();
+                    }
+
+                    if let Err(e) = self.register(&auth_req).await {
+                        resp_tx
+                            .send(NetworkResponse::RegistrationFailed(NetworkError::Auth(e)))
+                            .ok();
+                        continue;
+                    }
+                    resp_tx
+                        .send(NetworkResponse::Registered(auth_req.clone()))
+                        .ok();
+
+                    match self.auth(&auth_req).await {
+                        Ok(token) => {
+                            resp_tx.send(NetworkResponse::Auth(token)).ok();
+                        }
+                        Err(e) => {
+                            resp_tx
+                                .send(NetworkResponse::Error(NetworkError::Auth(e)))
+                                .ok();
+                        }
+                    }
+                }
+                NetworkRequest::SendMessage {
+                    content,
+                    channel,
+                    session,
+                } => match self.post_message(content, channel, &session).await {
+                    Ok(_) => {
+                        resp_tx.send(NetworkResponse::MessageSent).ok();
+                    }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::SendDirectMessage {
+                    content,
+                    recipient_id,
+                    session,
+                } => match self
+                    .post_direct_message(content, recipient_id, &session)
+                    .await
+                {
+                    Ok(_) => {
+                        resp_tx.send(NetworkResponse::MessageSent).ok();
                     }
                     Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::EditMessage {
+                    id,
+                    content,
+                    direct,
+                    session,
+                } => match self.edit_message(id, &content, direct, &session).await {
+                    Ok(edited_at) => {
                         resp_tx
-                            .send(NetworkResponse::Error(NetworkError::Auth(e)))
+                            .send(NetworkResponse::MessageEdited {
+                                id,
+                                direct,
+                                content,
+                                edited_at,
+                            })
                             .ok();
                     }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
                 },
-                NetworkRequest::SendMessage { content, session } => {
-                    match self.post_message(content, &session).await {
-                        Ok(_) => {
-                            resp_tx.send(NetworkResponse::MessageSent).ok();
+                NetworkRequest::DeleteMessage {
+                    id,
+                    direct,
+                    session,
+                } => match self.delete_message(id, direct, &session).await {
+                    Ok(_) => {
+                        resp_tx
+                            .send(NetworkResponse::MessageDeleted { id, direct })
+                            .ok();
+                    }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::AddReaction {
+                    message_id,
+                    emoji,
+                    direct,
+                    session,
+                } => match self
+                    .add_reaction(message_id, &emoji, direct, &session)
+                    .await
+                {
+                    Ok(_) => {
+                        resp_tx
+                            .send(NetworkResponse::ReactionChanged {
+                                message_id,
+                                direct,
+                                emoji,
+                                user_id: session.user_id,
+                                added: true,
+                            })
+                            .ok();
+                    }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::RemoveReaction {
+                    message_id,
+                    emoji,
+                    direct,
+                    session,
+                } => match self
+                    .remove_reaction(message_id, &emoji, direct, &session)
+                    .await
+                {
+                    Ok(_) => {
+                        resp_tx
+                            .send(NetworkResponse::ReactionChanged {
+                                message_id,
+                                direct,
+                                emoji,
+                                user_id: session.user_id,
+                                added: false,
+                            })
+                            .ok();
+                    }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::UploadAttachment {
+                    path,
+                    channel,
+                    session,
+                } => {
+                    let client = self.client.clone();
+                    let base_url = self.base_url.clone();
+                    let resp_tx = resp_tx.clone();
+                    tokio::spawn(async move {
+                        let filename = file_name(&path);
+                        match upload_attachment(
+                            &client, &base_url, &path, &channel, &session, &resp_tx,
+                        )
+                        .await
+                        {
+                            Ok(_) => {
+                                resp_tx.send(NetworkResponse::UploadFinished(filename)).ok();
+                                resp_tx.send(NetworkResponse::MessageSent).ok();
+                            }
+                            Err(error) if error.is_session_expired() => {
+                                resp_tx.send(error_response(error, &retry)).ok();
+                            }
+                            Err(error) => {
+                                resp_tx
+                                    .send(NetworkResponse::UploadFailed { filename, error })
+                                    .ok();
+                            }
                         }
-                        Err(e) => {
-                            resp_tx.send(NetworkResponse::Error(e)).ok();
+                    });
+                }
+                NetworkRequest::DownloadAttachment {
+                    attachment,
+                    directory,
+                    session,
+                } => {
+                    let client = self.client.clone();
+                    let base_url = self.base_url.clone();
+                    let resp_tx = resp_tx.clone();
+                    tokio::spawn(async move {
+                        match download_attachment(
+                            &client,
+                            &base_url,
+                            &attachment,
+                            &directory,
+                            &session,
+                        )
+                        .await
+                        {
+                            Ok(path) => {
+                                resp_tx.send(NetworkResponse::AttachmentSaved(path)).ok();
+                            }
+                            Err(e) => {
+                                resp_tx.send(error_response(e, &retry)).ok();
+                            }
                         }
+                    });
+                }
+                NetworkRequest::Typing { channel, session } => {
+                    let throttled = self.last_typing_sent.as_ref().is_some_and(|(last, at)| {
+                        *last == channel && at.elapsed() < TYPING_THROTTLE
+                    });
+                    if !self.typing_supported || throttled {
+                        continue;
+                    }
+
+                    self.last_typing_sent = Some((channel.clone(), Instant::now()));
+                    match self.send_typing(&channel, &session).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            tracing::info!("server does not support typing indicators");
+                            self.typing_supported = false;
+                        }
+                        Err(e) => tracing::debug!("failed to send typing signal: {e}"),
+                    }
+                }
+                NetworkRequest::FetchTyping(session) => {
+                    if !self.typing_supported {
+                        continue;
+                    }
+
+                    match self.fetch_typing(&session).await {
+                        Ok(Some(typing)) => {
+                            resp_tx.send(NetworkResponse::TypingReceived(typing)).ok();
+                        }
+                        Ok(None) => {
+                            tracing::info!("server does not support typing indicators");
+                            self.typing_supported = false;
+                        }
+                        Err(e) if e.is_session_expired() => {
+                            resp_tx.send(error_response(e, &retry)).ok();
+                        }
+                        Err(e) => tracing::debug!("failed to fetch typing users: {e}"),
+                    }
+                }
+                NetworkRequest::MarkRead {
+                    channel,
+                    message_id,
+                    session,
+                } => {
+                    if !self.read_sync_supported {
+                        continue;
+                    }
+
+                    match self.mark_read(&channel, message_id, &session).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            tracing::info!("server does not support read receipts");
+                            self.read_sync_supported = false;
+                        }
+                        Err(e) => tracing::debug!("failed to sync read state: {e}"),
                     }
                 }
                 NetworkRequest::FetchMessages => match self.fetch_messages().await {
                     Ok(messages) => {
+                        if self.should_refresh_users(&messages) {
+                            match self.fetch_users().await {
+                                Ok(users) => {
+                                    resp_tx.send(NetworkResponse::UsersReceived(users)).ok();
+                                }
+                                Err(e) => {
+                                    resp_tx.send(error_response(e, &retry)).ok();
+                                }
+                            }
+                        }
+
                         resp_tx
                             .send(NetworkResponse::MessagesReceived(
                                 messages
                                     .iter()
-                                    .map(|m| Message {
-                                        timestamp: m.created_at,
-                                        sender: MessageSender::User(m.user_id),
-                                        content: m.body.clone(),
-                                        username: self.users_map.get(&m.user_id).cloned(),
-                                    })
+                                    .map(|m| self.to_message(m))
                                     .collect::<Vec<Message>>(),
                             ))
                             .ok();
                     }
                     Err(e) => {
-                        resp_tx.send(NetworkResponse::Error(e)).ok();
+                        resp_tx.send(error_response(e, &retry)).ok();
+                    }
+                },
+                NetworkRequest::FetchDirectMessages(session) => {
+                    match self.fetch_direct_messages(&session).await {
+                        Ok(messages) => {
+                            resp_tx
+                                .send(NetworkResponse::DirectMessagesReceived(
+                                    messages
+                                        .iter()
+                                        .map(|m| self.to_direct_message(m, session.user_id))
+                                        .collect::<Vec<Message>>(),
+                                ))
+                                .ok();
+                        }
+                        Err(e) => {
+                            resp_tx.send(error_response(e, &retry)).ok();
+                        }
+                    }
+                }
+                NetworkRequest::FetchUsers => match self.fetch_users().await {
+                    Ok(users) => {
+                        resp_tx.send(NetworkResponse::UsersReceived(users)).ok();
+                    }
+                    Err(e) => {
+                        resp_tx.send(error_response(e, &retry)).ok();
                     }
                 },
+                NetworkRequest::UpdateProfile { name, session } => {
+                    match self.update_profile(name, &session).await {
+                        Ok(users) => {
+                            resp_tx.send(NetworkResponse::UsersReceived(users)).ok();
+                        }
+                        Err(e) => {
+                            resp_tx.send(error_response(e, &retry)).ok();
+                        }
+                    }
+                }
+                NetworkRequest::TestConnection(server_url) => {
+                    let result = self.test_connection(&server_url).await;
+                    resp_tx
+                        .send(NetworkResponse::ConnectionTested { server_url, result })
+                        .ok();
+                }
+                NetworkRequest::SetServer(base_url) => {
+                    self.base_url = base_url;
+                    self.users_map.clear();
+                    self.last_users_fetch = None;
+                    self.typing_supported = true;
+                    self.read_sync_supported = true;
+                    self.last_typing_sent = None;
+                    self.upgrade_checked = false;
+                }
                 NetworkRequest::RefreshToken => todo!(),
             }
         }
     }
 
-    async fn post_message(&self, content: String, session: &Token) -> Result<(), NetworkError> {
+    fn to_message(&self, message: &ServerMessage) -> Message {
+        Message {
+            id: Some(message.id),
+            channel: Channel::Public(message.channel.clone()),
+            timestamp: message.created_at,
+            sender: MessageSender::User(message.user_id),
+            content: message.body.clone(),
+            username: self.users_map.get(&message.user_id).cloned(),
+            edited_at: message.edited_at,
+            reactions: to_reactions(&message.reactions),
+            attachments: message.attachments.clone(),
+        }
+    }
+
+    fn to_direct_message(&self, message: &ServerDirectMessage, own_id: u32) -> Message {
+        let partner = if message.sender_id == own_id {
+            message.recipient_id
+        } else {
+            message.sender_id
+        };
+
+        Message {
+            id: Some(message.id),
+            channel: Channel::Direct(partner),
+            timestamp: message.created_at,
+            sender: MessageSender::User(message.sender_id),
+            content: message.body.clone(),
+            username: self.users_map.get(&message.sender_id).cloned(),
+            edited_at: message.edited_at,
+            reactions: to_reactions(&message.reactions),
+            attachments: message.attachments.clone(),
+        }
+    }
+
+    fn should_refresh_users(&self, messages: &[ServerMessage]) -> bool {
+        let has_unknown = messages
+            .iter()
+            .any(|m| !self.users_map.contains_key(&m.user_id));
+        let can_refresh = self
+            .last_users_fetch
+            .is_none_or(|last| last.elapsed() >= UNKNOWN_USERS_REFRESH_INTERVAL);
+
+        has_unknown && can_refresh
+    }
+
+    async fn post_message(
+        &self,
+        content: String,
+        channel: String,
+        session: &Token,
+    ) -> Result<(), NetworkError> {
         let response = self
             .client
             .post(format!("{}/messages", self.base_url))
             .header("Content-Type", "application/json")
             .header("Authorization", format!("Bearer {}", session.token))
+            .json(&json!(
+                {
+                    "body": content,
+                    "channel": channel
+                }
+            ))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
+        Ok(())
+    }
+
+    async fn post_direct_message(
+        &self,
+        content: String,
+        recipient_id: u32,
+        session: &Token,
+    ) -> Result<(), NetworkError> {
+        let response = self
+            .client
+            .post(format!("{}/dms", self.base_url))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&json!(
+                {
+                    "body": content,
+                    "recipient_id": recipient_id
+                }
+            ))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
+        Ok(())
+    }
+
+    fn message_url(&self, id: u32, direct: bool) -> String {
+        let collection = if direct { "dms" } else { "messages" };
+        format!("{}/{collection}/{id}", self.base_url)
+    }
+
+    async fn edit_message(
+        &self,
+        id: u32,
+        content: &str,
+        direct: bool,
+        session: &Token,
+    ) -> Result<DateTime<Utc>, NetworkError> {
+        let response = self
+            .client
+            .put(self.message_url(id, direct))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", session.token))
             .json(&json!(
                 {
                     "body": content
@@ -179,9 +906,189 @@ impl NetworkTask {
             .await?;
         response.error_for_status_ref()?;
 
+        // Older servers reply without a body, so fall back to the local clock.
+        let edited_at = response
+            .json::<ServerMessage>()
+            .await
+            .ok()
+            .and_then(|message| message.edited_at)
+            .unwrap_or_else(Utc::now);
+
+        Ok(edited_at)
+    }
+
+    async fn delete_message(
+        &self,
+        id: u32,
+        direct: bool,
+        session: &Token,
+    ) -> Result<(), NetworkError> {
+        let response = self
+            .client
+            .delete(self.message_url(id, direct))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
         Ok(())
     }
 
+    async fn add_reaction(
+        &self,
+        message_id: u32,
+        emoji: &str,
+        direct: bool,
+        session: &Token,
+    ) -> Result<(), NetworkError> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/reactions",
+                self.message_url(message_id, direct)
+            ))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&json!(
+                {
+                    "emoji": emoji
+                }
+            ))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
+        Ok(())
+    }
+
+    async fn remove_reaction(
+        &self,
+        message_id: u32,
+        emoji: &str,
+        direct: bool,
+        session: &Token,
+    ) -> Result<(), NetworkError> {
+        let response = self
+            .client
+            .delete(format!(
+                "{}/reactions",
+                self.message_url(message_id, direct)
+            ))
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&json!(
+                {
+                    "emoji": emoji
+                }
+            ))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
+        Ok(())
+    }
+
+    async fn send_typing(&self, channel: &Channel, session: &Token) -> Result<bool, NetworkError> {
+        let payload = match channel {
+            Channel::Public(channel) => json!({ "channel": channel }),
+            Channel::Direct(recipient_id) => json!({ "recipient_id": recipient_id }),
+        };
+        let response = self
+            .client
+            .post(format!("{}/typing", self.base_url))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&payload)
+            .send()
+            .await?;
+        if is_unsupported(response.status()) {
+            return Ok(false);
+        }
+        response.error_for_status_ref()?;
+
+        Ok(true)
+    }
+
+    async fn mark_read(
+        &self,
+        channel: &Channel,
+        message_id: u32,
+        session: &Token,
+    ) -> Result<bool, NetworkError> {
+        let payload = match channel {
+            Channel::Public(channel) => json!({ "channel": channel, "message_id": message_id }),
+            Channel::Direct(user_id) => json!({ "user_id": user_id, "message_id": message_id }),
+        };
+        let response = self
+            .client
+            .put(format!("{}/read_state", self.base_url))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&payload)
+            .send()
+            .await?;
+        if is_unsupported(response.status()) {
+            return Ok(false);
+        }
+        response.error_for_status_ref()?;
+
+        Ok(true)
+    }
+
+    async fn fetch_typing(&self, session: &Token) -> Result<Option<Vec<TypingUser>>, NetworkError> {
+        let response = self
+            .client
+            .get(format!("{}/typing", self.base_url))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .send()
+            .await?;
+        if is_unsupported(response.status()) {
+            return Ok(None);
+        }
+        response.error_for_status_ref()?;
+        let typing = response
+            .json::<Vec<ServerTyping>>()
+            .await
+            .map_err(NetworkError::Deserialize)?;
+
+        Ok(Some(
+            typing
+                .into_iter()
+                .filter(|t| t.user_id != session.user_id)
+                .filter_map(|t| {
+                    let channel = match (t.channel, t.recipient_id) {
+                        (Some(channel), _) => Channel::Public(channel),
+                        (None, Some(recipient)) if recipient == session.user_id => {
+                            Channel::Direct(t.user_id)
+                        }
+                        _ => return None,
+                    };
+                    Some(TypingUser {
+                        user_id: t.user_id,
+                        channel,
+                    })
+                })
+                .collect(),
+        ))
+    }
+
+    async fn fetch_direct_messages(
+        &self,
+        session: &Token,
+    ) -> Result<Vec<ServerDirectMessage>, NetworkError> {
+        let response: Response = self
+            .client
+            .get(format!("{}/dms", self.base_url))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+        let messages = response
+            .json::<Vec<ServerDirectMessage>>()
+            .await
+            .map_err(NetworkError::Deserialize)?;
+
+        Ok(messages)
+    }
+
     async fn fetch_messages(&self) -> Result<Vec<ServerMessage>, NetworkError> {
         let response: Response = self
             .client
@@ -192,12 +1099,138 @@ impl NetworkTask {
         let messages = response
             .json::<Vec<ServerMessage>>()
             .await
-            .map_err(|e| NetworkError::Deserialize(e))?;
+            .map_err(NetworkError::Deserialize)?;
 
         Ok(messages)
     }
 
+    async fn update_profile(
+        &mut self,
+        name: String,
+        session: &Token,
+    ) -> Result<Vec<User>, NetworkError> {
+        let response = self
+            .client
+            .put(format!("{}/users/{}", self.base_url, session.user_id))
+            .header("Authorization", format!("Bearer {}", session.token))
+            .json(&json!(
+                {
+                    "name": name
+                }
+            ))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+
+        self.fetch_users().await
+    }
+
+    async fn fetch_users(&mut self) -> Result<Vec<User>, NetworkError> {
+        self.last_users_fetch = Some(Instant::now());
+        let response: Response = self
+            .client
+            .get(format!("{}/users", self.base_url))
+            .send()
+            .await?;
+        response.error_for_status_ref()?;
+        let users = response
+            .json::<Vec<User>>()
+            .await
+            .map_err(NetworkError::Deserialize)?;
+
+        self.users_map = users
+            .iter()
+            .map(|u| (u.id, u.name.as_str().into()))
+            .collect();
+
+        Ok(users)
+    }
+
+    // Like an HSTS upgrade: before the first login to an http:// server, check whether the
+    // same address also answers over TLS and switch to it if so.
+    async fn upgrade_to_https(&mut self) -> Option<String> {
+        if self.upgrade_checked {
+            return None;
+        }
+        self.upgrade_checked = true;
+
+        let https_url = http::https_url(&self.base_url)?;
+        match self
+            .client
+            .get(&https_url)
+            .timeout(UPGRADE_PROBE_TIMEOUT)
+            .send()
+            .await
+        {
+            Ok(_) => {
+                tracing::info!("{} supports https, upgrading", self.base_url);
+                self.base_url = https_url.clone();
+                Some(https_url)
+            }
+            Err(e) => {
+                tracing::debug!("https upgrade for {} failed: {e}", self.base_url);
+                None
+            }
+        }
+    }
+
+    // Any HTTP response counts, the server only has to answer. A plaintext server we're not
+    // allowed to log in to passes if it can be upgraded, since logging in would do that.
+    async fn test_connection(&self, server_url: &str) -> Result<Duration, NetworkError> {
+        let url = if http::transport(server_url) == Transport::Plaintext && !self.allow_plaintext {
+            let https_url = http::https_url(server_url)
+                .ok_or_else(|| AuthError::Plaintext(http::host(server_url)))?;
+            let probe = self
+                .client
+                .get(&https_url)
+                .timeout(UPGRADE_PROBE_TIMEOUT)
+                .send()
+                .await;
+            if probe.is_err() {
+                return Err(AuthError::Plaintext(http::host(server_url)).into());
+            }
+            https_url
+        } else {
+            server_url.to_string()
+        };
+
+        let started = Instant::now();
+        self.client.get(&url).send().await?;
+        Ok(started.elapsed())
+    }
+
+    async fn register(&self, auth_req: &AuthRequest) -> Result<(), AuthError> {
+        if http::transport(&self.base_url) == Transport::Plaintext && !self.allow_plaintext {
+            return Err(AuthError::Plaintext(http::host(&self.base_url)));
+        }
+
+        let response = self
+            .client
+            .post(format!("{}/auth/register", self.base_url))
+            .json(auth_req)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == StatusCode::CONFLICT {
+            return Err(AuthError::UsernameTaken(auth_req.name.clone()));
+        }
+        if !status.is_success() {
+            let response_text = response.text().await?;
+
+            return Err(AuthError::Status {
+                status,
+                body: response_text,
+            });
+        }
+        Ok(())
+    }
+
     async fn auth(&mut self, auth_req: &AuthRequest) -> Result<Arc<Token>, AuthError> {
+        if http::transport(&self.base_url) == Transport::Plaintext && !self.allow_plaintext {
+            return Err(AuthError::Plaintext(http::host(&self.base_url)));
+        }
+
         let response = self
             .client
             .post(format!("{}/auth/login", self.base_url))
@@ -205,24 +1238,20 @@ impl NetworkTask {
             .send()
             .await?;
 
-        if let Err(e) = response.error_for_status_ref() {
+        let status = response.status();
+        if !status.is_success() {
             let response_text = response.text().await?;
 
             return Err(AuthError::Status {
-                status: format!("{e:?}"),
+                status,
                 body: response_text,
             });
         }
 
-        if self.users_map.is_empty() {
-            let response: Response = self
-                .client
-                .get(format!("{}/users", self.base_url))
-                .send()
-                .await?;
-            response.error_for_status_ref()?;
-            let users = response.json::<Vec<User>>().await?;
-            self.users_map = users.into_iter().map(|u| (u.id, u.name.into())).collect()
+        if self.users_map.is_empty()
+            && let Err(e) = self.fetch_users().await
+        {
+            tracing::warn!("failed to fetch users: {e}");
         }
 
         let mut des_response = response.json::<Token>().await?;
@@ -230,3 +1259,426 @@ impl NetworkTask {
         Ok(des_response.into())
     }
 }
+
+fn to_reactions(reactions: &[ServerReaction]) -> Vec<Reaction> {
+    let mut grouped: Vec<Reaction> = Vec::new();
+    for reaction in reactions {
+        match grouped.iter_mut().find(|r| r.emoji == reaction.emoji) {
+            Some(existing) => existing.user_ids.push(reaction.user_id),
+            None => grouped.push(Reaction {
+                emoji: reaction.emoji.clone(),
+                user_ids: vec![reaction.user_id],
+            }),
+        }
+    }
+    grouped
+}
+
+fn is_unsupported(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+    )
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+async fn upload_attachment(
+    client: &reqwest::Client,
+    base_url: &str,
+    path: &Path,
+    channel: &Channel,
+    session: &Token,
+    resp_tx: &mpsc::UnboundedSender<NetworkResponse>,
+) -> Result<(), NetworkError> {
+    let filename = file_name(path);
+    let file = tokio::fs::File::open(path).await?;
+    let total = file.metadata().await?.len();
+
+    // Read the file as it's sent rather than holding it all in memory.
+    let progress_tx = resp_tx.clone();
+    let progress_name = filename.clone();
+    let mut sent = 0;
+    let stream = ReaderStream::with_capacity(file, UPLOAD_CHUNK_SIZE).map(move |chunk| {
+        if let Ok(chunk) = &chunk {
+            sent += chunk.len() as u64;
+            progress_tx
+                .send(NetworkResponse::UploadProgress {
+                    filename: progress_name.clone(),
+                    sent,
+                    total,
+                })
+                .ok();
+        }
+        chunk
+    });
+
+    let part = Part::stream_with_length(Body::wrap_stream(stream), total).file_name(filename);
+    let response = client
+        .post(format!("{base_url}/attachments"))
+        .header("Authorization", format!("Bearer {}", session.token))
+        .multipart(Form::new().part("file", part))
+        .timeout(TRANSFER_TIMEOUT)
+        .send()
+        .await?;
+    response.error_for_status_ref()?;
+    let attachment = response
+        .json::<Attachment>()
+        .await
+        .map_err(NetworkError::Deserialize)?;
+
+    let (collection, payload) = match channel {
+        Channel::Public(channel) => (
+            "messages",
+            json!(
+                {
+                    "body": "",
+                    "channel": channel,
+                    "attachment_ids": [attachment.id]
+                }
+            ),
+        ),
+        Channel::Direct(recipient_id) => (
+            "dms",
+            json!(
+                {
+                    "body": "",
+                    "recipient_id": recipient_id,
+                    "attachment_ids": [attachment.id]
+                }
+            ),
+        ),
+    };
+    let response = client
+        .post(format!("{base_url}/{collection}"))
+        .header("Authorization", format!("Bearer {}", session.token))
+        .json(&payload)
+        .send()
+        .await?;
+    response.error_for_status_ref()?;
+
+    Ok(())
+}
+
+async fn download_attachment(
+    client: &reqwest::Client,
+    base_url: &str,
+    attachment: &Attachment,
+    directory: &Path,
+    session: &Token,
+) -> Result<PathBuf, NetworkError> {
+    let response = client
+        .get(format!("{base_url}/attachments/{}", attachment.id))
+        .header("Authorization", format!("Bearer {}", session.token))
+        .timeout(TRANSFER_TIMEOUT)
+        .send()
+        .await?;
+    response.error_for_status_ref()?;
+
+    tokio::fs::create_dir_all(directory).await?;
+    let path = unique_path(directory, &attachment.filename).await;
+    let mut file = tokio::fs::File::create(&path).await?;
+    let mut stream = response.bytes_stream();
+    let written = async {
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?).await?;
+        }
+        file.flush().await?;
+        Ok::<_, NetworkError>(())
+    }
+    .await;
+
+    // Don't leave a truncated file behind looking like a finished download.
+    if let Err(e) = written {
+        tokio::fs::remove_file(&path).await.ok();
+        return Err(e);
+    }
+    Ok(path)
+}
+
+async fn unique_path(directory: &Path, filename: &str) -> PathBuf {
+    // Never trust a server supplied name to stay inside the download directory.
+    let filename = Path::new(filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "attachment".to_string());
+    let (stem, extension) = match filename.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (filename.as_str(), None),
+    };
+
+    let mut path = directory.join(&filename);
+    let mut copy = 1;
+    while tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        let candidate = match extension {
+            Some(extension) => format!("{stem} ({copy}).{extension}"),
+            None => format!("{stem} ({copy})"),
+        };
+        path = directory.join(candidate);
+        copy += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tokio::{io::AsyncReadExt, net::TcpListener};
+
+    use super::*;
+
+    const OWN_ID: u32 = 1;
+    const TOKEN: &str = "secret";
+
+    // A stand-in for the message server that keeps direct messages in memory. It answers one
+    // request per connection, which is all the client needs.
+    struct MockServer {
+        url: String,
+        direct_messages: Arc<Mutex<Vec<serde_json::Value>>>,
+    }
+
+    impl MockServer {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let direct_messages = Arc::new(Mutex::new(Vec::new()));
+
+            let store = direct_messages.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let store = store.clone();
+                    tokio::spawn(async move {
+                        let (request_line, headers, body) = read_request(&mut stream).await;
+                        let (status, body) = respond(&store, &request_line, &headers, &body);
+                        let response = format!(
+                            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                            body.len()
+                        );
+                        stream.write_all(response.as_bytes()).await.ok();
+                    });
+                }
+            });
+
+            Self {
+                url,
+                direct_messages,
+            }
+        }
+    }
+
+    async fn read_request(stream: &mut tokio::net::TcpStream) -> (String, String, String) {
+        let mut data = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            let read = stream.read(&mut chunk).await.unwrap();
+            assert!(read > 0, "connection closed mid-request");
+            data.extend_from_slice(&chunk[..read]);
+            let text = String::from_utf8_lossy(&data).into_owned();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    let (request_line, headers) = head.split_once("\r\n").unwrap_or((head, ""));
+                    return (
+                        request_line.to_string(),
+                        headers.to_lowercase(),
+                        body.to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    fn respond(
+        store: &Mutex<Vec<serde_json::Value>>,
+        request_line: &str,
+        headers: &str,
+        body: &str,
+    ) -> (&'static str, String) {
+        if !headers.contains(&format!("authorization: bearer {TOKEN}")) {
+            return (
+                "401 Unauthorized",
+                json!({ "detail": "bad token" }).to_string(),
+            );
+        }
+
+        let mut store = store.lock().unwrap();
+        match request_line.split_whitespace().take(2).collect::<Vec<_>>()[..] {
+            ["GET", "/dms"] => ("200 OK", serde_json::Value::from(store.clone()).to_string()),
+            ["POST", "/dms"] => {
+                let request: serde_json::Value = serde_json::from_str(body).unwrap();
+                let message = json!({
+                    "id": store.len() + 1,
+                    "body": request["body"],
+                    "sender_id": OWN_ID,
+                    "recipient_id": request["recipient_id"],
+                    "created_at": "2024-01-01T00:00:00Z",
+                });
+                store.push(message.clone());
+                ("201 Created", message.to_string())
+            }
+            _ => ("404 Not Found", "{}".to_string()),
+        }
+    }
+
+    fn session(token: &str) -> Arc<Token> {
+        Arc::new(Token {
+            token: token.to_string(),
+            user_id: OWN_ID,
+            expiry: u64::MAX,
+            username: None,
+        })
+    }
+
+    fn start_task(
+        server: &MockServer,
+    ) -> (
+        mpsc::UnboundedSender<NetworkRequest>,
+        mpsc::UnboundedReceiver<NetworkResponse>,
+    ) {
+        let (req_tx, req_rx) = mpsc::unbounded_channel();
+        let (resp_tx, resp_rx) = mpsc::unbounded_channel();
+        req_tx
+            .send(NetworkRequest::SetServer(server.url.clone()))
+            .unwrap();
+        tokio::spawn(async move {
+            NetworkTask::new(reqwest::Client::new(), false)
+                .run(req_rx, resp_tx)
+                .await;
+        });
+        (req_tx, resp_rx)
+    }
+
+    fn status_error(status: StatusCode) -> NetworkError {
+        let response = Response::from(
+            ::http::Response::builder()
+                .status(status)
+                .body(String::new())
+                .unwrap(),
+        );
+        NetworkError::RequestError(response.error_for_status().unwrap_err())
+    }
+
+    #[test]
+    fn rejected_sessions_hand_the_request_back() {
+        let retry = Some(NetworkRequest::FetchDirectMessages(session(TOKEN)));
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            assert!(matches!(
+                error_response(status_error(status), &retry),
+                NetworkResponse::SessionExpired(NetworkRequest::FetchDirectMessages(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn other_failures_are_reported() {
+        let retry = Some(NetworkRequest::FetchDirectMessages(session(TOKEN)));
+        for status in [StatusCode::NOT_FOUND, StatusCode::INTERNAL_SERVER_ERROR] {
+            assert!(matches!(
+                error_response(status_error(status), &retry),
+                NetworkResponse::Error(_)
+            ));
+        }
+        // Requests without a session, like fetching public messages, can't be re-sent.
+        assert!(matches!(
+            error_response(status_error(StatusCode::UNAUTHORIZED), &None),
+            NetworkResponse::Error(_)
+        ));
+    }
+
+    #[test]
+    fn login_rejections_are_not_session_expiry() {
+        let error = NetworkError::Auth(AuthError::Status {
+            status: StatusCode::UNAUTHORIZED,
+            body: String::new(),
+        });
+        assert!(error.is_auth_rejected());
+        assert!(error.is_unauthorized());
+        assert!(!error.is_session_expired());
+
+        let error = NetworkError::Auth(AuthError::Status {
+            status: StatusCode::FORBIDDEN,
+            body: String::new(),
+        });
+        assert!(error.is_auth_rejected());
+        assert!(!error.is_unauthorized());
+    }
+
+    #[tokio::test]
+    async fn sends_and_fetches_direct_messages() {
+        let server = MockServer::start().await;
+        let (req_tx, mut resp_rx) = start_task(&server);
+
+        req_tx
+            .send(NetworkRequest::SendDirectMessage {
+                content: "hello there".to_string(),
+                recipient_id: 2,
+                session: session(TOKEN),
+            })
+            .unwrap();
+        assert!(matches!(
+            resp_rx.recv().await,
+            Some(NetworkResponse::MessageSent)
+        ));
+        server.direct_messages.lock().unwrap().push(json!({
+            "id": 2,
+            "body": "hi",
+            "sender_id": 2,
+            "recipient_id": OWN_ID,
+            "created_at": "2024-01-01T00:01:00Z",
+        }));
+
+        req_tx
+            .send(NetworkRequest::FetchDirectMessages(session(TOKEN)))
+            .unwrap();
+        let Some(NetworkResponse::DirectMessagesReceived(messages)) = resp_rx.recv().await else {
+            panic!("expected direct messages");
+        };
+
+        // Both sides of the conversation land in the partner's channel.
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "hello there");
+        assert_eq!(messages[0].channel, Channel::Direct(2));
+        assert_eq!(messages[0].sender, MessageSender::User(OWN_ID));
+        assert_eq!(messages[1].content, "hi");
+        assert_eq!(messages[1].channel, Channel::Direct(2));
+        assert_eq!(messages[1].sender, MessageSender::User(2));
+    }
+
+    #[tokio::test]
+    async fn hands_back_direct_messages_sent_with_a_rejected_session() {
+        let server = MockServer::start().await;
+        let (req_tx, mut resp_rx) = start_task(&server);
+
+        req_tx
+            .send(NetworkRequest::SendDirectMessage {
+                content: "hello".to_string(),
+                recipient_id: 2,
+                session: session("expired"),
+            })
+            .unwrap();
+        let Some(NetworkResponse::SessionExpired(request)) = resp_rx.recv().await else {
+            panic!("expected the request to be handed back");
+        };
+        assert!(matches!(
+            request,
+            NetworkRequest::SendDirectMessage {
+                recipient_id: 2,
+                ..
+            }
+        ));
+        assert!(server.direct_messages.lock().unwrap().is_empty());
+    }
+}
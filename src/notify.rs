@@ -0,0 +1,132 @@
+use std::io::Write;
+
+use crate::{
+    components::{Config, Message},
+    state::AppState,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Notifier {
+    bell: bool,
+    osc9: bool,
+    osc777: bool,
+    dbus: bool,
+}
+
+impl Notifier {
+    pub fn from_env() -> Self {
+        let methods =
+            std::env::var("MESSAGE_CLIENT_NOTIFY").unwrap_or_else(|_| "bell,osc9".to_string());
+        let enabled = |name: &str| methods.split(',').any(|m| m.trim() == name);
+
+        let dbus = enabled("dbus");
+        if dbus && !cfg!(all(feature = "dbus", target_os = "linux")) {
+            tracing::warn!("D-Bus notifications require building with the `dbus` feature on Linux");
+        }
+
+        Self {
+            bell: enabled("bell"),
+            osc9: enabled("osc9"),
+            osc777: enabled("osc777"),
+            dbus,
+        }
+    }
+
+    pub fn notify_messages(&self, state: &AppState, config: &Config, messages: &[Message]) {
+        let username = state
+            .session_token
+            .as_ref()
+            .and_then(|token| token.username.clone());
+
+        for message in messages {
+            let mentioned = username
+                .as_deref()
+                .is_some_and(|name| message.mentions(name));
+            let channel_name = state.channel_name(&message.channel);
+
+            if !mentioned && config.is_muted(&channel_name) {
+                continue;
+            }
+            let visible = state.background_account.is_none()
+                && state.terminal_focused
+                && message.channel == state.active_channel;
+            if visible {
+                continue;
+            }
+
+            let title = if mentioned {
+                format!(
+                    "{} mentioned you in {}",
+                    message.sender_name(),
+                    channel_name
+                )
+            } else if message.channel.is_direct() {
+                format!("{} (direct message)", message.sender_name())
+            } else {
+                format!("{} in {}", message.sender_name(), channel_name)
+            };
+            let title = match &state.background_account {
+                Some(account) => format!("[{account}] {title}"),
+                None => title,
+            };
+            self.notify(&title, &message.content);
+        }
+    }
+
+    pub fn notify(&self, title: &str, body: &str) {
+        let title = sanitize(title);
+        let body = sanitize(body);
+
+        let mut sequence = String::new();
+        if self.bell {
+            sequence.push('\x07');
+        }
+        if self.osc9 {
+            sequence.push_str(&format!("\x1b]9;{title}: {body}\x07"));
+        }
+        if self.osc777 {
+            sequence.push_str(&format!(
+                "\x1b]777;notify;{};{}\x07",
+                title.replace(';', ","),
+                body.replace(';', ",")
+            ));
+        }
+
+        if !sequence.is_empty() {
+            let mut stdout = std::io::stdout();
+            if let Err(e) = stdout
+                .write_all(sequence.as_bytes())
+                .and_then(|_| stdout.flush())
+            {
+                tracing::warn!("failed to write terminal notification: {e}");
+            }
+        }
+
+        if self.dbus {
+            dbus_notify(title, body);
+        }
+    }
+}
+
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+#[cfg(all(feature = "dbus", target_os = "linux"))]
+fn dbus_notify(title: String, body: String) {
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = notify_rust::Notification::new()
+            .appname("message-client")
+            .summary(&title)
+            .body(&body)
+            .show()
+        {
+            tracing::warn!("failed to send desktop notification: {e}");
+        }
+    });
+}
+
+#[cfg(not(all(feature = "dbus", target_os = "linux")))]
+fn dbus_notify(_title: String, _body: String) {}
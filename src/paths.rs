@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+const APP_DIR: &str = "message-client";
+
+pub fn state_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::state_dir)
+        .or_else(dirs::data_local_dir)
+        .map(|dir| dir.join(APP_DIR))
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(dirs::config_dir)
+        .map(|dir| dir.join(APP_DIR))
+}
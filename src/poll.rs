@@ -1,8 +1,6 @@
 use std::time::Duration;
 
-use tokio::{sync::mpsc, time::Instant};
-
-use crate::network::NetworkRequest;
+use tokio::time::Instant;
 
 pub struct Poll<F> {
     last_poll: Instant,
@@ -0,0 +1,106 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{components::Channel, paths};
+
+const FILE_NAME: &str = "read_state.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct ReadStateFile {
+    servers: HashMap<String, HashMap<String, u32>>,
+}
+
+pub struct ReadState {
+    path: Option<PathBuf>,
+    server: String,
+    file: ReadStateFile,
+    pub sync: bool,
+}
+
+impl ReadState {
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            server: String::new(),
+            file: ReadStateFile::default(),
+            sync: false,
+        }
+    }
+
+    pub fn load(server: &str) -> Self {
+        let path = paths::state_dir().map(|dir| dir.join(FILE_NAME));
+        let file = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| match serde_json::from_str(&contents) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    tracing::warn!("ignoring unreadable read state: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let sync = std::env::var("MESSAGE_CLIENT_SYNC_READ")
+            .is_ok_and(|value| value == "1" || value.eq_ignore_ascii_case("true"));
+
+        Self {
+            path,
+            server: server.to_string(),
+            file,
+            sync,
+        }
+    }
+
+    pub fn set_server(&mut self, server: &str) {
+        self.server = server.to_string();
+    }
+
+    pub fn last_read(&self, channel: &Channel) -> Option<u32> {
+        self.file
+            .servers
+            .get(&self.server)?
+            .get(&channel_key(channel))
+            .copied()
+    }
+
+    pub fn mark(&mut self, channel: &Channel, message_id: u32) -> bool {
+        let last_read = self
+            .file
+            .servers
+            .entry(self.server.clone())
+            .or_default()
+            .entry(channel_key(channel))
+            .or_default();
+        if *last_read >= message_id {
+            return false;
+        }
+
+        *last_read = message_id;
+        true
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| {
+                let contents = serde_json::to_string_pretty(&self.file)?;
+                fs::write(path, contents)
+            });
+        if let Err(e) = result {
+            tracing::warn!("failed to save read state to {}: {e}", path.display());
+        }
+    }
+}
+
+fn channel_key(channel: &Channel) -> String {
+    match channel {
+        Channel::Public(name) => format!("#{name}"),
+        Channel::Direct(user_id) => format!("@{user_id}"),
+    }
+}
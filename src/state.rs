@@ -1,60 +1,731 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
 
 use chrono::{DateTime, Utc};
 
 use crate::{
-    components::{ConnectionStatus, Message, MessageSender},
-    network::Token,
+    backoff, commands,
+    completion::{self, Completion},
+    components::{Channel, ConnectionStatus, Message, MessageSender, Reaction, Severity, Toast},
+    emoji,
+    network::{AuthRequest, NetworkError, NetworkRequest, Token, TypingUser, User},
+    read_state::ReadState,
+    text,
 };
 
+const TYPING_TIMEOUT: Duration = Duration::from_secs(6);
+const MAX_TOASTS: usize = 100;
+const MAX_PENDING_REQUESTS: usize = 50;
+const RENEWAL_GRACE: Duration = Duration::from_secs(10);
+
+pub struct Upload {
+    pub filename: String,
+    pub sent: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FocusedItem {
     Main,
+    Messages,
+    Members,
     Config,
+    LinkPicker,
+    EmojiPicker,
+    Logs,
+    Notifications,
+    AccountPicker,
+    Register,
+    Login,
 }
 
 pub struct AppState {
     pub messages: Vec<Message>,
+    // Whether public messages and DMs have been fetched since connecting to this server.
+    pub loaded_public: bool,
+    pub loaded_direct: bool,
     pub input_buffer: String,
     pub cursor_position: usize,
     pub scroll_offset: u16,
     pub connection_status: ConnectionStatus,
     pub focused_item: FocusedItem,
     pub session_token: Option<Arc<Token>>,
-    pub last_reconnect: Option<tokio::time::Instant>,
-    pub reconnect_duration: Duration,
+    pub server_url: String,
+    pub connection_attempts: u32,
+    pub last_error: Option<String>,
+    pub pending_requests: Vec<NetworkRequest>,
+    pub renewed_session: Option<(Arc<Token>, tokio::time::Instant)>,
+    pub users: Vec<User>,
+    pub show_members: bool,
+    pub selected_member: usize,
+    pub active_channel: Channel,
+    pub open_direct: Vec<u32>,
+    pub terminal_focused: bool,
+    pub completion: Option<Completion>,
+    pub cleared_at: HashMap<Channel, DateTime<Utc>>,
+    pub selected_message: usize,
+    // The message being edited, by id and whether it's a DM.
+    pub editing: Option<(u32, bool)>,
+    // The message awaiting a second `d` to confirm its deletion, keyed the same way.
+    pub pending_delete: Option<(u32, bool)>,
+    pub upload: Option<Upload>,
+    pub typing: Vec<(TypingUser, tokio::time::Instant)>,
+    pub read_state: ReadState,
+    pub unread_divider: Option<u32>,
+    pub toasts: VecDeque<Toast>,
+    pub account_name: Option<String>,
+    pub background_account: Option<String>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let mut messages = Vec::new();
-
-        messages.push(Message {
+        let messages = vec![Message {
+            id: None,
+            channel: Channel::default(),
             sender: MessageSender::System,
             content: "Welcome to Message Client! Start typing to send messages.".to_string(),
             timestamp: Utc::now(),
             username: None,
-        });
+            edited_at: None,
+            reactions: Vec::new(),
+            attachments: Vec::new(),
+        }];
 
         Self {
             messages,
+            loaded_public: false,
+            loaded_direct: false,
             input_buffer: String::new(),
             cursor_position: 0,
             scroll_offset: 0,
-            connection_status: ConnectionStatus::Disconnected,
+            connection_status: ConnectionStatus::Idle,
             focused_item: FocusedItem::Main,
             session_token: None,
-            last_reconnect: None,
-            reconnect_duration: Duration::from_secs(5),
+            server_url: String::new(),
+            connection_attempts: 0,
+            last_error: None,
+            pending_requests: Vec::new(),
+            renewed_session: None,
+            users: Vec::new(),
+            show_members: false,
+            selected_member: 0,
+            active_channel: Channel::default(),
+            open_direct: Vec::new(),
+            terminal_focused: true,
+            completion: None,
+            cleared_at: HashMap::new(),
+            selected_message: 0,
+            editing: None,
+            pending_delete: None,
+            upload: None,
+            typing: Vec::new(),
+            read_state: ReadState::new(),
+            unread_divider: None,
+            toasts: VecDeque::new(),
+            account_name: None,
+            background_account: None,
         }
     }
 
+    // Exchanges all per-account state with `other`, keeping what belongs to the terminal
+    // session (input, focus, toasts) in place. Swapping twice restores the original.
+    pub fn swap_account(&mut self, other: &mut AppState) {
+        std::mem::swap(self, other);
+        std::mem::swap(&mut self.input_buffer, &mut other.input_buffer);
+        std::mem::swap(&mut self.cursor_position, &mut other.cursor_position);
+        std::mem::swap(&mut self.focused_item, &mut other.focused_item);
+        std::mem::swap(&mut self.show_members, &mut other.show_members);
+        std::mem::swap(&mut self.terminal_focused, &mut other.terminal_focused);
+        std::mem::swap(&mut self.completion, &mut other.completion);
+        // The delete confirmation stays with the screen too, so a background account's event
+        // doesn't disarm it. Switching accounts for real clears it.
+        std::mem::swap(&mut self.pending_delete, &mut other.pending_delete);
+        std::mem::swap(&mut self.toasts, &mut other.toasts);
+        std::mem::swap(&mut self.account_name, &mut other.account_name);
+        std::mem::swap(&mut self.background_account, &mut other.background_account);
+    }
+
     pub fn update_session(&mut self, token: Option<Arc<Token>>) {
-        if token.is_some() {
-            self.connection_status = ConnectionStatus::Connected;
-        }
+        self.connection_status = if token.is_some() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Idle
+        };
         self.session_token = token;
     }
 
+    // Drives the connection state machine, returning a login request when it's time to try.
+    pub fn poll_connection(&mut self, username: &str, password: &str) -> Option<AuthRequest> {
+        match &self.connection_status {
+            ConnectionStatus::Connected => {
+                if self
+                    .session_token
+                    .as_ref()
+                    .is_some_and(|token| token.is_valid())
+                {
+                    return None;
+                }
+                self.session_token = None;
+            }
+            ConnectionStatus::Backoff { until } if tokio::time::Instant::now() < *until => {
+                return None;
+            }
+            ConnectionStatus::Connecting | ConnectionStatus::Failed => return None,
+            ConnectionStatus::Idle
+            | ConnectionStatus::NeedsCredentials
+            | ConnectionStatus::Backoff { .. } => {}
+        }
+
+        if username.is_empty() || password.is_empty() {
+            self.connection_status = ConnectionStatus::NeedsCredentials;
+            return None;
+        }
+
+        self.connection_status = ConnectionStatus::Connecting;
+        Some(AuthRequest {
+            name: username.to_string(),
+            password: password.to_string(),
+        })
+    }
+
+    // Returns the requests that failed on the expired session, re-addressed to the new one.
+    pub fn connection_established(&mut self, token: Arc<Token>) -> Vec<NetworkRequest> {
+        self.connection_attempts = 0;
+        self.last_error = None;
+        self.update_session(Some(token.clone()));
+
+        if self.pending_requests.is_empty() {
+            return Vec::new();
+        }
+        self.renewed_session = Some((token.clone(), tokio::time::Instant::now()));
+        self.pending_requests
+            .drain(..)
+            .map(|request| request.with_session(token.clone()))
+            .collect()
+    }
+
+    // Queues the rejected request and drops the session so the connection loop logs in
+    // again. A request that already raced a newer session is returned to re-send now.
+    pub fn session_expired(&mut self, mut request: NetworkRequest) -> Option<NetworkRequest> {
+        let session = request.session_mut()?.clone();
+
+        // A session that was just renewed being rejected again is a permission problem,
+        // retrying would only loop.
+        if let Some((renewed, at)) = &self.renewed_session
+            && Arc::ptr_eq(renewed, &session)
+            && at.elapsed() < RENEWAL_GRACE
+        {
+            self.notify(
+                Severity::Error,
+                "The server refused the request, you may not have permission".to_string(),
+            );
+            return None;
+        }
+
+        let is_current = self
+            .session_token
+            .as_ref()
+            .is_some_and(|token| Arc::ptr_eq(token, &session));
+        if !is_current && let Some(token) = self.session_token.clone() {
+            return Some(request.with_session(token));
+        }
+
+        if self.pending_requests.len() < MAX_PENDING_REQUESTS {
+            self.pending_requests.push(request);
+        }
+        if is_current {
+            self.update_session(None);
+            self.last_error = Some("Session expired".to_string());
+            self.notify(
+                Severity::Warning,
+                "Session expired, logging in again".to_string(),
+            );
+        }
+        None
+    }
+
+    // Rejected credentials won't fix themselves, so those stop retrying until the user asks.
+    pub fn connection_failed(&mut self, error: &NetworkError) {
+        self.session_token = None;
+        self.last_error = Some(error.user_message());
+        self.connection_status = if error.is_auth_rejected() {
+            ConnectionStatus::Failed
+        } else {
+            self.connection_attempts += 1;
+            ConnectionStatus::Backoff {
+                until: tokio::time::Instant::now() + backoff::delay(self.connection_attempts),
+            }
+        };
+    }
+
+    pub fn retry_connection(&mut self) {
+        self.connection_attempts = 0;
+        self.update_session(None);
+    }
+
+    pub fn own_username(&self) -> Option<Arc<str>> {
+        self.session_token
+            .as_ref()
+            .and_then(|token| token.username.clone())
+    }
+
+    pub fn mentions_me(&self, message: &Message) -> bool {
+        self.own_username()
+            .is_some_and(|name| message.mentions(&name))
+    }
+
+    pub fn last_activity(&self, user_id: u32) -> Option<DateTime<Utc>> {
+        self.messages
+            .iter()
+            .filter(|m| m.sender == MessageSender::User(user_id))
+            .map(|m| m.timestamp)
+            .max()
+    }
+
+    pub fn set_users(&mut self, users: Vec<User>) {
+        if let Some(token) = &self.session_token
+            && let Some(user) = users.iter().find(|u| u.id == token.user_id)
+            && token.username.as_deref() != Some(user.name.as_str())
+        {
+            let mut token = Token::clone(token);
+            token.username = Some(user.name.as_str().into());
+            self.session_token = Some(Arc::new(token));
+        }
+
+        for message in &mut self.messages {
+            if let MessageSender::User(id) = message.sender
+                && let Some(user) = users.iter().find(|u| u.id == id)
+                && message.username.as_deref() != Some(user.name.as_str())
+            {
+                message.username = Some(user.name.as_str().into());
+            }
+        }
+        self.users = users;
+    }
+
+    pub fn cycle_focus(&mut self, forward: bool) {
+        let order: &[FocusedItem] = if self.show_members {
+            &[
+                FocusedItem::Main,
+                FocusedItem::Messages,
+                FocusedItem::Members,
+            ]
+        } else {
+            &[FocusedItem::Main, FocusedItem::Messages]
+        };
+        let current = order
+            .iter()
+            .position(|item| *item == self.focused_item)
+            .unwrap_or(0);
+        let next = if forward {
+            (current + 1) % order.len()
+        } else {
+            (current + order.len() - 1) % order.len()
+        };
+
+        self.focused_item = order[next];
+        if matches!(self.focused_item, FocusedItem::Messages) {
+            self.selected_message = 0;
+        }
+    }
+
+    pub fn selected_message(&self) -> Option<&Message> {
+        self.channel_messages().nth(self.selected_message)
+    }
+
+    pub fn select_previous_message(&mut self) {
+        self.pending_delete = None;
+        self.selected_message = self.selected_message.saturating_sub(1);
+    }
+
+    pub fn select_next_message(&mut self) {
+        self.pending_delete = None;
+        let last = self.channel_messages().count().saturating_sub(1);
+        self.selected_message = (self.selected_message + 1).min(last);
+    }
+
+    fn own_selected_message(&self) -> Result<(u32, &Message), &'static str> {
+        let own_id = self
+            .session_token
+            .as_ref()
+            .map(|token| token.user_id)
+            .ok_or("You must be connected to change messages")?;
+        let message = self.selected_message().ok_or("No message selected")?;
+        if message.sender != MessageSender::User(own_id) {
+            return Err("You can only change your own messages");
+        }
+        let id = message
+            .id
+            .ok_or("This message hasn't reached the server yet")?;
+
+        Ok((id, message))
+    }
+
+    pub fn start_edit(&mut self) {
+        match self.own_selected_message() {
+            Ok((id, message)) => {
+                let direct = message.channel.is_direct();
+                self.input_buffer = message.content.clone();
+                self.cursor_position = self.input_buffer.len();
+                self.completion = None;
+                self.editing = Some((id, direct));
+                self.focused_item = FocusedItem::Main;
+            }
+            Err(reason) => self.system_message(reason.to_string()),
+        }
+    }
+
+    pub fn delete_selected(&mut self) -> Option<NetworkRequest> {
+        let (id, direct) = match self.own_selected_message() {
+            Ok((id, message)) => (id, message.channel.is_direct()),
+            Err(reason) => {
+                self.system_message(reason.to_string());
+                return None;
+            }
+        };
+
+        if self.pending_delete != Some((id, direct)) {
+            self.pending_delete = Some((id, direct));
+            return None;
+        }
+
+        self.pending_delete = None;
+        Some(NetworkRequest::DeleteMessage {
+            id,
+            direct,
+            session: self.session_token.clone()?,
+        })
+    }
+
+    // Public messages and DMs are numbered separately, so an id alone is ambiguous.
+    fn find_message_mut(&mut self, id: u32, direct: bool) -> Option<&mut Message> {
+        self.messages
+            .iter_mut()
+            .find(|m| m.id == Some(id) && m.channel.is_direct() == direct)
+    }
+
+    pub fn apply_edit(&mut self, id: u32, direct: bool, content: String, edited_at: DateTime<Utc>) {
+        if let Some(message) = self.find_message_mut(id, direct) {
+            message.content = content;
+            message.edited_at = Some(edited_at);
+        }
+    }
+
+    pub fn remove_message(&mut self, id: u32, direct: bool) {
+        self.messages
+            .retain(|m| m.id != Some(id) || m.channel.is_direct() != direct);
+        let last = self.channel_messages().count().saturating_sub(1);
+        self.selected_message = self.selected_message.min(last);
+    }
+
+    pub fn toggle_reaction(&mut self, emoji: &str) -> Option<NetworkRequest> {
+        let session = self.session_token.clone()?;
+        let message = self.selected_message()?;
+        let Some(message_id) = message.id else {
+            self.system_message("This message hasn't reached the server yet".to_string());
+            return None;
+        };
+
+        let emoji = emoji.to_string();
+        let direct = message.channel.is_direct();
+        Some(if message.reacted(&emoji, session.user_id) {
+            NetworkRequest::RemoveReaction {
+                message_id,
+                emoji,
+                direct,
+                session,
+            }
+        } else {
+            NetworkRequest::AddReaction {
+                message_id,
+                emoji,
+                direct,
+                session,
+            }
+        })
+    }
+
+    pub fn apply_reaction(
+        &mut self,
+        message_id: u32,
+        direct: bool,
+        emoji: String,
+        user_id: u32,
+        added: bool,
+    ) {
+        let Some(message) = self.find_message_mut(message_id, direct) else {
+            return;
+        };
+
+        match message.reactions.iter_mut().position(|r| r.emoji == emoji) {
+            Some(index) => {
+                let reaction = &mut message.reactions[index];
+                reaction.user_ids.retain(|id| *id != user_id);
+                if added {
+                    reaction.user_ids.push(user_id);
+                } else if reaction.user_ids.is_empty() {
+                    message.reactions.remove(index);
+                }
+            }
+            None if added => message.reactions.push(Reaction {
+                emoji,
+                user_ids: vec![user_id],
+            }),
+            None => {}
+        }
+    }
+
+    pub fn typing_request(&self) -> Option<NetworkRequest> {
+        if self.input_buffer.is_empty() || self.input_buffer.starts_with('/') {
+            return None;
+        }
+
+        Some(NetworkRequest::Typing {
+            channel: self.active_channel.clone(),
+            session: self.session_token.clone()?,
+        })
+    }
+
+    pub fn set_typing(&mut self, typing: Vec<TypingUser>) {
+        let now = tokio::time::Instant::now();
+        self.typing = typing.into_iter().map(|user| (user, now)).collect();
+    }
+
+    pub fn typing_names(&self) -> Vec<String> {
+        self.typing
+            .iter()
+            .filter(|(user, seen)| {
+                user.channel == self.active_channel && seen.elapsed() < TYPING_TIMEOUT
+            })
+            .map(|(user, _)| {
+                self.user_name(user.user_id)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("User: {}", user.user_id))
+            })
+            .collect()
+    }
+
+    pub fn toggle_members(&mut self) {
+        self.show_members = !self.show_members;
+        if !self.show_members && matches!(self.focused_item, FocusedItem::Members) {
+            self.focused_item = FocusedItem::Main;
+        }
+    }
+
+    pub fn user_name(&self, user_id: u32) -> Option<&str> {
+        self.users
+            .iter()
+            .find(|u| u.id == user_id)
+            .map(|u| u.name.as_str())
+    }
+
+    pub fn channel_name(&self, channel: &Channel) -> String {
+        match channel {
+            Channel::Public(name) => format!("#{name}"),
+            Channel::Direct(id) => match self.user_name(*id) {
+                Some(name) => format!("@{name}"),
+                None => format!("@User: {id}"),
+            },
+        }
+    }
+
+    pub fn channels(&self) -> Vec<Channel> {
+        let mut public: Vec<Channel> = Vec::new();
+        let mut direct: Vec<Channel> = Vec::new();
+
+        let known = self
+            .messages
+            .iter()
+            .map(|m| &m.channel)
+            .chain(std::iter::once(&self.active_channel))
+            .cloned()
+            .chain(self.open_direct.iter().map(|id| Channel::Direct(*id)));
+        for channel in known {
+            let list = if channel.is_direct() {
+                &mut direct
+            } else {
+                &mut public
+            };
+            if !list.contains(&channel) {
+                list.push(channel);
+            }
+        }
+
+        public.sort_by_key(|c| self.channel_name(c));
+        direct.sort_by_key(|c| self.channel_name(c).to_lowercase());
+        public.extend(direct);
+        public
+    }
+
+    pub fn channel_messages(&self) -> impl Iterator<Item = &Message> {
+        let cleared_at = self.cleared_at.get(&self.active_channel);
+        self.messages
+            .iter()
+            .filter(|m| m.channel == self.active_channel)
+            .filter(move |m| cleared_at.is_none_or(|cleared| m.timestamp > *cleared))
+    }
+
+    fn unread_messages(&self, channel: &Channel) -> impl Iterator<Item = &Message> {
+        let last_read = self.read_state.last_read(channel).unwrap_or(0);
+        let own_id = self.session_token.as_ref().map(|token| token.user_id);
+        self.messages
+            .iter()
+            .filter(move |m| m.channel == *channel)
+            .filter(move |m| m.id.is_some_and(|id| id > last_read))
+            .filter(move |m| matches!(m.sender, MessageSender::User(id) if Some(id) != own_id))
+    }
+
+    pub fn unread_count(&self, channel: &Channel) -> usize {
+        self.unread_messages(channel).count()
+    }
+
+    pub fn mention_count(&self, channel: &Channel) -> usize {
+        self.unread_messages(channel)
+            .filter(|m| self.mentions_me(m))
+            .count()
+    }
+
+    pub fn total_unread(&self) -> usize {
+        self.channels()
+            .iter()
+            .map(|channel| self.unread_count(channel))
+            .sum()
+    }
+
+    pub fn update_unread_divider(&mut self) {
+        self.unread_divider = if self.unread_count(&self.active_channel) > 0 {
+            Some(self.read_state.last_read(&self.active_channel).unwrap_or(0))
+        } else {
+            None
+        };
+    }
+
+    pub fn mark_read(&mut self) -> Option<NetworkRequest> {
+        if !self.terminal_focused || self.scroll_offset > 0 {
+            return None;
+        }
+
+        let latest = self.channel_messages().filter_map(|m| m.id).max()?;
+        if !self.read_state.mark(&self.active_channel, latest) {
+            return None;
+        }
+        self.read_state.save();
+
+        if !self.read_state.sync {
+            return None;
+        }
+        Some(NetworkRequest::MarkRead {
+            channel: self.active_channel.clone(),
+            message_id: latest,
+            session: self.session_token.clone()?,
+        })
+    }
+
+    pub fn clear_channel(&mut self) {
+        self.cleared_at
+            .insert(self.active_channel.clone(), Utc::now());
+        self.scroll_offset = 0;
+    }
+
+    pub fn switch_channel(&mut self, channel: Channel) {
+        self.active_channel = channel;
+        self.scroll_offset = 0;
+        self.selected_message = 0;
+        self.pending_delete = None;
+        self.update_unread_divider();
+    }
+
+    pub fn cycle_channel(&mut self, forward: bool) {
+        let channels = self.channels();
+        let Some(current) = channels.iter().position(|c| *c == self.active_channel) else {
+            return;
+        };
+        let next = if forward {
+            (current + 1) % channels.len()
+        } else {
+            (current + channels.len() - 1) % channels.len()
+        };
+        self.switch_channel(channels[next].clone());
+    }
+
+    pub fn open_direct(&mut self, user_id: u32) {
+        if !self.open_direct.contains(&user_id) {
+            self.open_direct.push(user_id);
+        }
+        self.switch_channel(Channel::Direct(user_id));
+    }
+
+    pub fn set_messages(&mut self, direct: bool, messages: Vec<Message>) -> Vec<Message> {
+        let known: HashSet<u32> = self
+            .messages
+            .iter()
+            .filter(|m| m.channel.is_direct() == direct)
+            .filter_map(|m| m.id)
+            .collect();
+        let own_id = self.session_token.as_ref().map(|token| token.user_id);
+        let loaded = if direct {
+            &mut self.loaded_direct
+        } else {
+            &mut self.loaded_public
+        };
+        let first_fetch = !std::mem::replace(loaded, true);
+
+        // The first fetch is history, not news.
+        let new_messages: Vec<Message> = if first_fetch {
+            Vec::new()
+        } else {
+            messages
+                .iter()
+                .filter(|m| m.id.is_some_and(|id| !known.contains(&id)))
+                .filter(|m| own_id.is_none_or(|own| m.sender != MessageSender::User(own)))
+                .cloned()
+                .collect()
+        };
+
+        // Without a stored position, everything already on the server counts as read.
+        if first_fetch {
+            let mut changed = false;
+            for message in &messages {
+                if let Some(id) = message.id
+                    && self
+                        .read_state
+                        .last_read(&message.channel)
+                        .is_none_or(|last| last == 0)
+                {
+                    changed |= self.read_state.mark(&message.channel, id);
+                }
+            }
+            if changed {
+                self.read_state.save();
+            }
+        }
+
+        // Servers that don't report edits would otherwise drop the marker on the next poll.
+        let edited: HashMap<u32, DateTime<Utc>> = self
+            .messages
+            .iter()
+            .filter(|m| m.channel.is_direct() == direct)
+            .filter_map(|m| Some((m.id?, m.edited_at?)))
+            .collect();
+        let messages = messages.into_iter().map(|mut message| {
+            if message.edited_at.is_none() {
+                message.edited_at = message.id.and_then(|id| edited.get(&id).copied());
+            }
+            message
+        });
+
+        self.messages
+            .retain(|m| m.sender == MessageSender::System || m.channel.is_direct() != direct);
+        self.messages.extend(messages);
+        self.messages.sort_by_key(|m| Reverse(m.timestamp));
+
+        new_messages
+    }
+
     pub fn add_message(
         &mut self,
         sender: MessageSender,
@@ -65,65 +736,176 @@ impl AppState {
         self.messages.insert(
             0,
             Message {
+                id: None,
+                channel: self.active_channel.clone(),
                 sender,
                 content,
                 timestamp,
                 username,
+                edited_at: None,
+                reactions: Vec::new(),
+                attachments: Vec::new(),
             },
         );
     }
 
-    pub fn send_message(&mut self) -> Option<String> {
-        if !self.input_buffer.trim().is_empty()
-            && let Some(token) = &self.session_token
+    pub fn system_message(&mut self, content: String) {
+        self.add_message(MessageSender::System, content, Utc::now(), None);
+    }
+
+    // Repeats of the latest toast are folded into it so a failing poll doesn't flood the screen.
+    pub fn notify(&mut self, severity: Severity, message: String) {
+        let message = match &self.background_account {
+            Some(account) => format!("[{account}] {message}"),
+            None => message,
+        };
+        if let Some(last) = self.toasts.back_mut()
+            && last.severity == severity
+            && last.message == message
         {
-            let message = self.input_buffer.clone();
-            self.add_message(
-                MessageSender::User(token.user_id),
-                message.clone(),
-                Utc::now(),
-                token.username.clone(),
-            );
-            self.input_buffer.clear();
-            self.cursor_position = 0;
-            return Some(message.clone());
+            last.count += 1;
+            last.shown_at = tokio::time::Instant::now();
+            last.time = chrono::Local::now();
+            last.dismissed = false;
+            return;
+        }
+
+        self.toasts.push_back(Toast::new(severity, message));
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    pub fn has_active_toasts(&self) -> bool {
+        let now = tokio::time::Instant::now();
+        self.toasts.iter().any(|toast| toast.is_active(now))
+    }
+
+    pub fn dismiss_toasts(&mut self) {
+        for toast in &mut self.toasts {
+            toast.dismissed = true;
         }
-        None
+    }
+
+    pub fn send_content(&mut self, content: String) -> Option<NetworkRequest> {
+        let session = self.session_token.clone()?;
+        let content = emoji::expand(&content);
+        self.add_message(
+            MessageSender::User(session.user_id),
+            content.clone(),
+            Utc::now(),
+            session.username.clone(),
+        );
+
+        Some(match &self.active_channel {
+            Channel::Public(channel) => NetworkRequest::SendMessage {
+                content,
+                channel: channel.clone(),
+                session,
+            },
+            Channel::Direct(recipient_id) => NetworkRequest::SendDirectMessage {
+                content,
+                recipient_id: *recipient_id,
+                session,
+            },
+        })
+    }
+
+    pub fn send_message(&mut self) -> Option<NetworkRequest> {
+        if self.input_buffer.trim().is_empty() {
+            return None;
+        }
+
+        let request = match self.editing {
+            Some((id, direct)) => NetworkRequest::EditMessage {
+                id,
+                content: emoji::expand(&self.input_buffer),
+                direct,
+                session: self.session_token.clone()?,
+            },
+            None => self.send_content(self.input_buffer.clone())?,
+        };
+        self.clear_input();
+        Some(request)
     }
 
     pub fn insert_char(&mut self, c: char) {
         self.input_buffer.insert(self.cursor_position, c);
-        self.cursor_position += 1;
+        self.cursor_position += c.len_utf8();
+        self.update_completion();
+    }
+
+    pub fn insert_str(&mut self, s: &str) {
+        self.input_buffer.insert_str(self.cursor_position, s);
+        self.cursor_position += s.len();
+        self.update_completion();
     }
 
     pub fn delete_char(&mut self) {
         if self.cursor_position < self.input_buffer.len() {
             self.input_buffer.remove(self.cursor_position);
         }
+        self.update_completion();
     }
 
     pub fn backspace(&mut self) {
         if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+            self.cursor_position =
+                text::previous_boundary(&self.input_buffer, self.cursor_position);
             self.input_buffer.remove(self.cursor_position);
         }
+        self.update_completion();
     }
 
     pub fn move_cursor_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-        }
+        self.cursor_position = text::previous_boundary(&self.input_buffer, self.cursor_position);
+        self.update_completion();
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_position < self.input_buffer.len() {
-            self.cursor_position += 1;
-        }
+        self.cursor_position = text::next_boundary(&self.input_buffer, self.cursor_position);
+        self.update_completion();
     }
 
     pub fn clear_input(&mut self) {
         self.input_buffer.clear();
         self.cursor_position = 0;
+        self.completion = None;
+        self.editing = None;
+    }
+
+    fn update_completion(&mut self) {
+        let (start, token) = completion::token_before(&self.input_buffer, self.cursor_position);
+
+        let candidates = if self.input_buffer.starts_with('/') {
+            commands::candidates(self, &self.input_buffer, start, token)
+        } else if let Some(prefix) = token.strip_prefix('@') {
+            completion::mention_candidates(prefix, self.users.iter().map(|u| u.name.as_str()))
+        } else if let Some(prefix) = token.strip_prefix(':')
+            && prefix.len() >= 2
+            && !prefix.contains(':')
+        {
+            emoji::search(prefix)
+                .into_iter()
+                .map(|(name, _)| format!(":{name}:"))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        self.completion = Completion::new(start, self.cursor_position, candidates);
+    }
+
+    pub fn complete(&mut self) {
+        let Some(completion) = &mut self.completion else {
+            return;
+        };
+
+        let replacement = completion.current().to_string();
+        self.input_buffer
+            .replace_range(completion.start..completion.end, &replacement);
+        self.cursor_position = completion.start + replacement.len();
+        completion.advance(replacement.len());
     }
 
     pub fn scroll_up(&mut self) {
@@ -0,0 +1,37 @@
+pub fn previous_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+pub fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map_or(index, |c| index + c.len_utf8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_over_whole_characters() {
+        let text = "aé👍";
+        assert_eq!(next_boundary(text, 0), 1);
+        assert_eq!(next_boundary(text, 1), 3);
+        assert_eq!(next_boundary(text, 3), 7);
+        assert_eq!(previous_boundary(text, 7), 3);
+        assert_eq!(previous_boundary(text, 3), 1);
+        assert_eq!(previous_boundary(text, 1), 0);
+    }
+
+    #[test]
+    fn stays_put_at_the_ends() {
+        assert_eq!(previous_boundary("abc", 0), 0);
+        assert_eq!(next_boundary("abc", 3), 3);
+        assert_eq!(previous_boundary("", 0), 0);
+        assert_eq!(next_boundary("", 0), 0);
+    }
+}
@@ -1,35 +1,104 @@
 use std::{
+    collections::VecDeque,
     io,
     sync::{Arc, Mutex},
 };
 
+use tracing::Level;
 use tracing_subscriber::fmt::MakeWriter;
 
+const MAX_LINES: usize = 5000;
+
+#[derive(Clone)]
+pub struct LogLine {
+    pub level: Level,
+    pub text: String,
+}
+
+struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    partial: Vec<u8>,
+}
+
+impl LogBuffer {
+    fn push_line(&mut self, bytes: &[u8]) {
+        let text = String::from_utf8_lossy(bytes).into_owned();
+        // Continuation lines of a multi-line event keep the level of the line that started it.
+        let level = parse_level(&text)
+            .or_else(|| self.lines.back().map(|line| line.level))
+            .unwrap_or(Level::INFO);
+
+        if self.lines.len() == MAX_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(LogLine { level, text });
+    }
+}
+
 #[derive(Clone)]
 pub struct BufferedWriter {
-    buffer: Arc<Mutex<Vec<u8>>>,
+    buffer: Arc<Mutex<LogBuffer>>,
 }
 
 impl BufferedWriter {
     pub fn new() -> Self {
         Self {
-            buffer: Arc::new(Mutex::new(Vec::new())),
+            buffer: Arc::new(Mutex::new(LogBuffer {
+                lines: VecDeque::new(),
+                partial: Vec::new(),
+            })),
         }
     }
 
     pub fn get_contents(&self) -> String {
         let buffer = self.buffer.lock().unwrap();
-        String::from_utf8_lossy(&buffer).to_string()
+        buffer
+            .lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn count(&self, max_level: Level) -> usize {
+        let buffer = self.buffer.lock().unwrap();
+        buffer
+            .lines
+            .iter()
+            .filter(|line| line.level <= max_level)
+            .count()
+    }
+
+    // Up to `take` lines, oldest first, ending `skip` lines before the newest.
+    pub fn lines(&self, max_level: Level, skip: usize, take: usize) -> Vec<LogLine> {
+        let buffer = self.buffer.lock().unwrap();
+        let mut lines: Vec<LogLine> = buffer
+            .lines
+            .iter()
+            .rev()
+            .filter(|line| line.level <= max_level)
+            .skip(skip)
+            .take(take)
+            .cloned()
+            .collect();
+        lines.reverse();
+        lines
     }
 }
 
 impl io::Write for BufferedWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.buffer.lock().unwrap().write(buf)
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.partial.extend_from_slice(buf);
+        while let Some(end) = buffer.partial.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.partial.drain(..=end).collect();
+            buffer.push_line(&line[..end]);
+        }
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.buffer.lock().unwrap().flush()
+        Ok(())
     }
 }
 
@@ -40,3 +109,10 @@ impl<'a> MakeWriter<'a> for BufferedWriter {
         self.clone()
     }
 }
+
+fn parse_level(line: &str) -> Option<Level> {
+    line.split_whitespace()
+        .take(3)
+        .filter(|word| word.chars().all(|c| c.is_ascii_alphabetic()))
+        .find_map(|word| word.parse::<Level>().ok())
+}